@@ -0,0 +1,45 @@
+//! Access to the standard attributes of vertex types.
+
+use crate::{Normal, Position, Tangent, TexCoord, Vertex};
+
+/// Gives mesh writers access to the standard attributes of a vertex type.
+///
+/// Implement this for your own vertex types to write them out directly.
+/// Only the position is required, the other attributes are written when
+/// they are present.
+pub trait VertexAttributes {
+    /// The position of the vertex.
+    fn position(&self) -> Position;
+
+    /// The normal of the vertex, if it has one.
+    fn normal(&self) -> Option<Normal> {
+        None
+    }
+
+    /// The texture coordinate of the vertex, if it has one.
+    fn tex_coord(&self) -> Option<TexCoord> {
+        None
+    }
+
+    /// The tangent of the vertex, if it has one, with the handedness of
+    /// the bitangent in `w`.
+    fn tangent(&self) -> Option<Tangent> {
+        None
+    }
+}
+
+impl VertexAttributes for Vertex {
+    fn position(&self) -> Position {
+        self.pos
+    }
+
+    fn normal(&self) -> Option<Normal> {
+        Some(self.normal)
+    }
+}
+
+impl VertexAttributes for Position {
+    fn position(&self) -> Position {
+        *self
+    }
+}
@@ -0,0 +1,124 @@
+//! A simple look-at camera for the renderers.
+
+use crate::math::Vector3;
+use crate::Position;
+
+/// How a [`Camera`] maps the view onto the screen.
+///
+/// [`Camera`]: struct.Camera.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// A perspective projection with a vertical field of view in radians.
+    Perspective {
+        /// The vertical field of view, in radians.
+        fov_y: f32,
+    },
+    /// A parallel projection showing a view of a given height.
+    Orthographic {
+        /// The height of the view, in world units.
+        height: f32,
+    },
+}
+
+/// A camera looking from an eye position at a target.
+///
+/// ```
+/// use genmesh::Camera;
+///
+/// let camera = Camera::new([0., -4., 0.].into(), [0., 0., 0.].into());
+/// let p = camera.project([0., 0., 0.].into(), 1.).unwrap();
+/// assert_eq!((p.x, p.y, p.z), (0., 0., 4.));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    eye: Position,
+    target: Position,
+    up: Position,
+    projection: Projection,
+    near: f32,
+}
+
+impl Camera {
+    /// Creates a new `Camera` at `eye` looking at `target`, with `+z` up
+    /// and a perspective projection with a 45 degree field of view.
+    pub fn new(eye: Position, target: Position) -> Self {
+        Camera {
+            eye,
+            target,
+            up: [0., 0., 1.].into(),
+            projection: Projection::Perspective {
+                fov_y: std::f32::consts::FRAC_PI_4,
+            },
+            near: 0.01,
+        }
+    }
+
+    /// Sets the direction that points up on the screen. When it is
+    /// parallel to the view, `+y` or `+x` is used instead.
+    pub fn up(self, up: Position) -> Self {
+        Camera { up, ..self }
+    }
+
+    /// Sets the projection.
+    pub fn projection(self, projection: Projection) -> Self {
+        Camera { projection, ..self }
+    }
+
+    /// Sets the distance of the near plane. Points closer to the eye than
+    /// it are not projected.
+    pub fn near(self, near: f32) -> Self {
+        Camera { near, ..self }
+    }
+
+    /// Projects a position for a screen with the given aspect ratio, width
+    /// over height.
+    ///
+    /// The visible part of the screen runs from -1 to 1 in `x` towards the
+    /// right and `y` upwards, while `z` is the distance in front of the
+    /// eye along the view. Returns `None` for positions nearer than the
+    /// near plane.
+    pub fn project(&self, p: Position, aspect: f32) -> Option<Position> {
+        let (right, up, forward) = self.basis();
+        let d = Vector3::from(p) - Vector3::from(self.eye);
+        let (x, y, z) = (d.dot(right), d.dot(up), d.dot(forward));
+        if z < self.near {
+            return None;
+        }
+        let (sx, sy) = match self.projection {
+            Projection::Perspective { fov_y } => {
+                let s = 1. / (fov_y / 2.).tan();
+                (x * s / (aspect * z), y * s / z)
+            }
+            Projection::Orthographic { height } => {
+                let s = 2. / height;
+                (x * s / aspect, y * s)
+            }
+        };
+        Some([sx, sy, z].into())
+    }
+
+    pub(crate) fn is_perspective(&self) -> bool {
+        matches!(self.projection, Projection::Perspective { .. })
+    }
+
+    // the unit direction from `p` towards the eye
+    pub(crate) fn towards_eye(&self, p: Vector3) -> Vector3 {
+        match self.projection {
+            Projection::Perspective { .. } => (Vector3::from(self.eye) - p).normalized(),
+            Projection::Orthographic { .. } => self.basis().2 * -1.,
+        }
+    }
+
+    fn basis(&self) -> (Vector3, Vector3, Vector3) {
+        let forward = (Vector3::from(self.target) - Vector3::from(self.eye)).normalized();
+        let mut right = forward.cross(Vector3::from(self.up));
+        for &fallback in &[Vector3::new(0., 1., 0.), Vector3::new(1., 0., 0.)] {
+            if right.magnitude_squared() > 1e-12 {
+                break;
+            }
+            right = forward.cross(fallback);
+        }
+        let right = right.normalized();
+        (right, right.cross(forward), forward)
+    }
+}
@@ -0,0 +1,179 @@
+//! Splitting indexed meshes into connected components, and merging
+//! several meshes into one.
+
+use std::ops::Range;
+
+use crate::generators::{IndexedPolygon, SharedVertex};
+use crate::topology::{find, union};
+use crate::{EmitVertices, MapVertex};
+
+/// A connected piece of an indexed mesh, as returned by
+/// [`split_components`].
+///
+/// [`split_components`]: fn.split_components.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct Component<T, P> {
+    /// The vertices of the piece.
+    pub vertices: Vec<T>,
+    /// The polygons of the piece, indexing into `vertices`.
+    pub polygons: Vec<P>,
+    /// The index in the source mesh of every vertex of the piece.
+    pub vertex_remap: Vec<usize>,
+    /// The index in the source mesh of every polygon of the piece.
+    pub polygon_remap: Vec<usize>,
+}
+
+/// Splits an indexed mesh into the pieces that do not share any vertex,
+/// like the fragments of a broken object.
+///
+/// Polygons that only touch at a single vertex belong to the same piece,
+/// which matches the components of a [`TopologyReport`], apart from the
+/// degenerate faces that the report leaves out.
+///
+/// Pieces are ordered by their first polygon, and keep the order of their
+/// vertices and polygons. Vertices that no polygon uses are dropped.
+///
+/// [`TopologyReport`]: struct.TopologyReport.html
+pub fn split_components<T, P>(vertices: &[T], polygons: &[P]) -> Vec<Component<T, P>>
+where
+    T: Clone,
+    P: EmitVertices<usize> + MapVertex<usize, usize, Output = P> + Clone,
+{
+    let mut group: Vec<usize> = (0..vertices.len()).collect();
+    let mut first = Vec::with_capacity(polygons.len());
+    for p in polygons {
+        let mut head = None;
+        p.clone().emit_vertices(|v| match head {
+            Some(h) => union(&mut group, h, v),
+            None => head = Some(v),
+        });
+        first.push(head);
+    }
+
+    // the piece of every root, and the local index of every vertex
+    let mut piece: Vec<Option<usize>> = vec![None; vertices.len()];
+    let mut local: Vec<usize> = vec![0; vertices.len()];
+    let mut components: Vec<Component<T, P>> = Vec::new();
+    for (i, p) in polygons.iter().enumerate() {
+        let root = match first[i] {
+            Some(v) => find(&mut group, v),
+            None => continue,
+        };
+        let c = *piece[root].get_or_insert_with(|| {
+            components.push(Component {
+                vertices: Vec::new(),
+                polygons: Vec::new(),
+                vertex_remap: Vec::new(),
+                polygon_remap: Vec::new(),
+            });
+            components.len() - 1
+        });
+        components[c].polygon_remap.push(i);
+        p.clone()
+            .emit_vertices(|v| components[c].vertex_remap.push(v));
+    }
+
+    for c in &mut components {
+        c.vertex_remap.sort_unstable();
+        c.vertex_remap.dedup();
+        for (i, &v) in c.vertex_remap.iter().enumerate() {
+            local[v] = i;
+        }
+        c.vertices = c
+            .vertex_remap
+            .iter()
+            .map(|&v| vertices[v].clone())
+            .collect();
+        c.polygons = c
+            .polygon_remap
+            .iter()
+            .map(|&i| polygons[i].clone().map_vertex(|v| local[v]))
+            .collect();
+    }
+    components
+}
+
+/// Several indexed meshes merged into a single vertex buffer, with their
+/// indices offset to match.
+///
+/// The polygons of each source are kept together, so that materials or
+/// other per-source data can be assigned to them afterwards.
+///
+/// ```
+/// use genmesh::generators::{Cube, IcoSphere};
+/// use genmesh::{MergedMesh, Polygon, Vertex};
+///
+/// let mut mesh: MergedMesh<Vertex, Polygon<usize>> = MergedMesh::new();
+/// let cube = mesh.add_generator(&Cube::new());
+/// let sphere = mesh.add_generator(&IcoSphere::new());
+/// assert_eq!(mesh.source(cube).len(), 6);
+/// assert_eq!(mesh.source(sphere).len(), 20);
+/// assert_eq!(mesh.vertices.len(), 24 + 12);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergedMesh<T, P> {
+    /// The vertices of all sources, one after another.
+    pub vertices: Vec<T>,
+    /// The polygons of all sources, one after another.
+    pub polygons: Vec<P>,
+    /// The range of `polygons` that came from each source, in the order
+    /// the sources were added.
+    pub sources: Vec<Range<usize>>,
+}
+
+impl<T, P> Default for MergedMesh<T, P> {
+    fn default() -> Self {
+        MergedMesh::new()
+    }
+}
+
+impl<T, P> MergedMesh<T, P> {
+    /// Creates an empty `MergedMesh`.
+    pub fn new() -> Self {
+        MergedMesh {
+            vertices: Vec::new(),
+            polygons: Vec::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    /// Adds an indexed mesh, and returns its source index. The polygons
+    /// index into `vertices`, and may be of any type that converts into
+    /// `P`, such as triangles into a `Polygon`.
+    pub fn add<V, I, Q>(&mut self, vertices: V, polygons: I) -> usize
+    where
+        V: IntoIterator<Item = T>,
+        I: IntoIterator<Item = Q>,
+        Q: Into<P>,
+        P: MapVertex<usize, usize, Output = P>,
+    {
+        let offset = self.vertices.len();
+        self.vertices.extend(vertices);
+        let start = self.polygons.len();
+        self.polygons.extend(
+            polygons
+                .into_iter()
+                .map(|p| p.into().map_vertex(|v| v + offset)),
+        );
+        self.sources.push(start..self.polygons.len());
+        self.sources.len() - 1
+    }
+
+    /// Adds the mesh of a generator, and returns its source index.
+    pub fn add_generator<G, Q>(&mut self, generator: &G) -> usize
+    where
+        G: SharedVertex<T> + IndexedPolygon<Q>,
+        Q: Into<P>,
+        P: MapVertex<usize, usize, Output = P>,
+    {
+        self.add(
+            generator.shared_vertex_iter(),
+            generator.indexed_polygon_iter(),
+        )
+    }
+
+    /// Returns the polygons of source `i`.
+    pub fn source(&self, i: usize) -> &[P] {
+        &self.polygons[self.sources[i].clone()]
+    }
+}
@@ -28,7 +28,7 @@ impl Cone {
     /// # Arguments
     ///
     /// - `u` is the number of subdivisions around the radius of the cone,
-    ///     it must be at least 2
+    ///   it must be at least 2
     ///
     /// # Panics
     ///
@@ -0,0 +1,243 @@
+//! Encoders and decoders for compact vertex attributes.
+//!
+//! These are the encodings used by the compact formats of [`Format`], for
+//! use on their own. Values are rounded to the nearest representable one,
+//! and clamped to the range of the encoding.
+//!
+//! ```
+//! use genmesh::encoding;
+//!
+//! let n = [0.6, 0., 0.8].into();
+//! let d = encoding::decode_octahedral16(encoding::encode_octahedral16(n));
+//! assert!((d.x - 0.6).abs() < 1e-4 && (d.z - 0.8).abs() < 1e-4);
+//!
+//! assert_eq!(encoding::decode_f16(encoding::encode_f16(0.5)), 0.5);
+//! ```
+//!
+//! [`Format`]: ../enum.Format.html
+
+use crate::{Normal, Position};
+
+/// Encodes a float as an IEEE 754 half float, rounding to the nearest even
+/// value. Values too large for a half float become infinite.
+pub fn encode_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        // a subnormal half float, or zero
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let rounded = mantissa + (1 << (shift - 1)) - 1 + ((mantissa >> shift) & 1);
+        return sign | (rounded >> shift) as u16;
+    }
+    // a carry out of the mantissa correctly bumps the exponent
+    let rounded = mantissa + 0xfff + ((mantissa >> 13) & 1);
+    sign | (((exponent as u32) << 10) + (rounded >> 13)) as u16
+}
+
+/// Decodes an IEEE 754 half float.
+pub fn decode_f16(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1. } else { 1. };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * (2f32).powi(-24),
+        0x1f if mantissa == 0. => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1. + mantissa / 1024.) * (2f32).powi(exponent - 15),
+    }
+}
+
+/// Encodes a value from -1 to 1 as a signed normalized integer of `bits`
+/// bits.
+///
+/// # Panics
+///
+/// This function panics if `bits` is not between 2 and 32.
+pub fn encode_snorm(value: f32, bits: u32) -> i32 {
+    (value.clamp(-1., 1.) as f64 * snorm_max(bits)).round() as i32
+}
+
+/// Decodes a signed normalized integer of `bits` bits.
+///
+/// # Panics
+///
+/// This function panics if `bits` is not between 2 and 32.
+pub fn decode_snorm(value: i32, bits: u32) -> f32 {
+    (value as f64 / snorm_max(bits)).max(-1.) as f32
+}
+
+// a single bit would leave no value for 1, and an f32 can not hold the
+// largest 32-bit values exactly
+fn snorm_max(bits: u32) -> f64 {
+    assert!((2..=32).contains(&bits));
+    ((1i64 << (bits - 1)) - 1) as f64
+}
+
+/// Encodes a value from 0 to 1 as an unsigned normalized integer of `bits`
+/// bits.
+///
+/// # Panics
+///
+/// This function panics if `bits` is not between 1 and 32.
+pub fn encode_unorm(value: f32, bits: u32) -> u32 {
+    (value.clamp(0., 1.) as f64 * unorm_max(bits)).round() as u32
+}
+
+/// Decodes an unsigned normalized integer of `bits` bits.
+///
+/// # Panics
+///
+/// This function panics if `bits` is not between 1 and 32.
+pub fn decode_unorm(value: u32, bits: u32) -> f32 {
+    (value as f64 / unorm_max(bits)) as f32
+}
+
+// zero bits would leave no value for 1
+fn unorm_max(bits: u32) -> f64 {
+    assert!((1..=32).contains(&bits));
+    ((1u64 << bits) - 1) as f64
+}
+
+/// Encodes a texture coordinate component from 0 to 1 as a 16-bit unsigned
+/// normalized integer.
+pub fn encode_unorm16(value: f32) -> u16 {
+    encode_unorm(value, 16) as u16
+}
+
+/// Decodes a 16-bit unsigned normalized integer.
+pub fn decode_unorm16(value: u16) -> f32 {
+    decode_unorm(value as u32, 16)
+}
+
+// maps a normal onto the octahedron unfolded into the square from -1 to 1
+fn octahedral(n: Normal) -> [f32; 2] {
+    let l1 = n.x.abs() + n.y.abs() + n.z.abs();
+    if l1 == 0. {
+        return [0., 0.];
+    }
+    let (x, y) = (n.x / l1, n.y / l1);
+    if n.z < 0. {
+        [(1. - y.abs()) * sign(x), (1. - x.abs()) * sign(y)]
+    } else {
+        [x, y]
+    }
+}
+
+fn from_octahedral([x, y]: [f32; 2]) -> Normal {
+    let z = 1. - x.abs() - y.abs();
+    let (x, y) = if z < 0. {
+        ((1. - y.abs()) * sign(x), (1. - x.abs()) * sign(y))
+    } else {
+        (x, y)
+    };
+    let length = (x * x + y * y + z * z).sqrt();
+    [x / length, y / length, z / length].into()
+}
+
+fn sign(value: f32) -> f32 {
+    if value < 0. {
+        -1.
+    } else {
+        1.
+    }
+}
+
+/// Encodes a unit normal in two 16-bit signed normalized integers, with an
+/// octahedral mapping. A zero normal decodes as `+z`.
+pub fn encode_octahedral16(n: Normal) -> [i16; 2] {
+    let [x, y] = octahedral(n);
+    [encode_snorm(x, 16) as i16, encode_snorm(y, 16) as i16]
+}
+
+/// Decodes a normal encoded by [`encode_octahedral16`].
+///
+/// [`encode_octahedral16`]: fn.encode_octahedral16.html
+pub fn decode_octahedral16([x, y]: [i16; 2]) -> Normal {
+    from_octahedral([decode_snorm(x as i32, 16), decode_snorm(y as i32, 16)])
+}
+
+/// Encodes a unit normal in two 8-bit signed normalized integers, with an
+/// octahedral mapping. A zero normal decodes as `+z`.
+pub fn encode_octahedral8(n: Normal) -> [i8; 2] {
+    let [x, y] = octahedral(n);
+    [encode_snorm(x, 8) as i8, encode_snorm(y, 8) as i8]
+}
+
+/// Decodes a normal encoded by [`encode_octahedral8`].
+///
+/// [`encode_octahedral8`]: fn.encode_octahedral8.html
+pub fn decode_octahedral8([x, y]: [i8; 2]) -> Normal {
+    from_octahedral([decode_snorm(x as i32, 8), decode_snorm(y as i32, 8)])
+}
+
+/// Encodes four values from -1 to 1 in 32 bits, as three 10-bit signed
+/// normalized integers followed by a 2-bit one, from the lowest bits up.
+///
+/// The last value only keeps its sign, which suits the handedness of a
+/// tangent.
+pub fn encode_snorm10_10_10_2(values: [f32; 4]) -> u32 {
+    let field = |value: f32, bits: u32| encode_snorm(value, bits) as u32 & ((1 << bits) - 1);
+    field(values[0], 10)
+        | field(values[1], 10) << 10
+        | field(values[2], 10) << 20
+        | field(values[3], 2) << 30
+}
+
+/// Decodes values encoded by [`encode_snorm10_10_10_2`].
+///
+/// [`encode_snorm10_10_10_2`]: fn.encode_snorm10_10_10_2.html
+pub fn decode_snorm10_10_10_2(packed: u32) -> [f32; 4] {
+    // shifting the field to the top and back extends its sign
+    let field = |shift: u32, bits: u32| {
+        let value = ((packed << (32 - shift - bits)) as i32) >> (32 - bits);
+        decode_snorm(value, bits)
+    };
+    [field(0, 10), field(10, 10), field(20, 10), field(30, 2)]
+}
+
+/// Encodes a position as three 16-bit unsigned normalized integers, relative
+/// to the box from `min` to `max`.
+pub fn encode_quantized(p: Position, min: Position, max: Position) -> [u16; 3] {
+    let axis = |value: f32, min: f32, max: f32| {
+        let extent = max - min;
+        let t = if extent > 0. {
+            (value - min) / extent
+        } else {
+            0.
+        };
+        encode_unorm16(t)
+    };
+    [
+        axis(p.x, min.x, max.x),
+        axis(p.y, min.y, max.y),
+        axis(p.z, min.z, max.z),
+    ]
+}
+
+/// Decodes a position encoded by [`encode_quantized`] with the same box.
+///
+/// [`encode_quantized`]: fn.encode_quantized.html
+pub fn decode_quantized(q: [u16; 3], min: Position, max: Position) -> Position {
+    let axis = |value: u16, min: f32, max: f32| min + decode_unorm16(value) * (max - min);
+    [
+        axis(q[0], min.x, max.x),
+        axis(q[1], min.y, max.y),
+        axis(q[2], min.z, max.z),
+    ]
+    .into()
+}
@@ -0,0 +1,53 @@
+//! Errors shared by the mesh file readers.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// An error while reading a mesh file.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The underlying reader failed.
+    Io(io::Error),
+    /// The file is malformed.
+    Parse {
+        /// The 1-based line the error was found on. Binary formats report
+        /// the line of the header, or `0` for the binary body.
+        line: usize,
+        /// What was wrong with it.
+        message: String,
+    },
+}
+
+impl ReadError {
+    pub(crate) fn parse<M: Into<String>>(line: usize, message: M) -> Self {
+        ReadError::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{}", e),
+            ReadError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
@@ -15,7 +15,7 @@ pub trait SharedVertex<V>: Sized {
 
     /// Create an [`Iterator`] that returns each shared vertex that is required to
     /// build the mesh.
-    fn shared_vertex_iter(&self) -> SharedVertexIterator<Self, V> {
+    fn shared_vertex_iter(&self) -> SharedVertexIterator<'_, Self, V> {
         SharedVertexIterator {
             base: self,
             idx: 0..self.shared_vertex_count(),
@@ -68,7 +68,7 @@ pub trait IndexedPolygon<V>: Sized {
     fn indexed_polygon_count(&self) -> usize;
 
     /// Creates an [`Iterator`] that will return a polygon for each face in the source mesh.
-    fn indexed_polygon_iter(&self) -> IndexedPolygonIterator<Self, V> {
+    fn indexed_polygon_iter(&self) -> IndexedPolygonIterator<'_, Self, V> {
         IndexedPolygonIterator {
             base: self,
             idx: 0..self.indexed_polygon_count(),
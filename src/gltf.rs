@@ -0,0 +1,427 @@
+//! Writing glTF 2.0 files, either as `.gltf` JSON with a separate `.bin`
+//! buffer, or as a single binary `.glb`.
+//!
+//! A file holds one indexed triangle mesh in a single scene. Positions are
+//! always written, while normals, texture coordinates and tangents are
+//! written when every vertex has them, as told by [`VertexAttributes`].
+//! Indices are written as `u16` when the mesh is small enough, and as `u32`
+//! otherwise.
+//!
+//! ```
+//! use genmesh::generators::{Cube, IndexedPolygon, SharedVertex};
+//! use genmesh::gltf::GltfWriter;
+//! use genmesh::Vertex;
+//!
+//! let cube = Cube::new();
+//! let vertices: Vec<Vertex> = cube.shared_vertex_iter().collect();
+//! let quads: Vec<_> = cube.indexed_polygon_iter().collect();
+//!
+//! let mut glb = Vec::new();
+//! GltfWriter::new().name("cube").write_glb(&mut glb, &vertices, &quads).unwrap();
+//! assert_eq!(&glb[..4], b"glTF");
+//! ```
+//!
+//! [`VertexAttributes`]: ../trait.VertexAttributes.html
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{EmitTriangles, VertexAttributes};
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_JSON: &[u8] = b"JSON";
+const GLB_BIN: &[u8] = b"BIN\0";
+
+/// Writes indexed meshes to glTF 2.0 files.
+///
+/// Every optional attribute can be left out even when the vertices have
+/// it. Texture coordinates are written as they are, so they should have
+/// their origin at the top left, as glTF expects.
+#[derive(Clone, Debug)]
+pub struct GltfWriter {
+    name: Option<String>,
+    normals: bool,
+    tex_coords: bool,
+    tangents: bool,
+}
+
+impl Default for GltfWriter {
+    fn default() -> Self {
+        GltfWriter::new()
+    }
+}
+
+// an accessor into its own buffer view
+struct Accessor {
+    component: u32,
+    kind: &'static str,
+    count: usize,
+    target: u32,
+    offset: usize,
+    length: usize,
+    min: Vec<f64>,
+    max: Vec<f64>,
+}
+
+// the binary buffer of a mesh along with the accessors into it
+struct Buffers {
+    data: Vec<u8>,
+    accessors: Vec<Accessor>,
+    attributes: Vec<(&'static str, usize)>,
+}
+
+impl Buffers {
+    fn push_floats<I>(
+        &mut self,
+        semantic: &'static str,
+        kind: &'static str,
+        values: I,
+    ) -> io::Result<()>
+    where
+        I: IntoIterator<Item = Vec<f32>>,
+    {
+        let offset = self.data.len();
+        let mut count = 0;
+        let mut min = Vec::new();
+        let mut max = Vec::new();
+        for value in values {
+            if count == 0 {
+                min = value.iter().map(|&c| c as f64).collect();
+                max = min.clone();
+            }
+            for (i, &c) in value.iter().enumerate() {
+                // the accessor bounds have no way to represent NaN or infinity
+                if !c.is_finite() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("non-finite {} value", semantic),
+                    ));
+                }
+                min[i] = min[i].min(c as f64);
+                max[i] = max[i].max(c as f64);
+                self.data.extend_from_slice(&c.to_le_bytes());
+            }
+            count += 1;
+        }
+        self.attributes.push((semantic, self.accessors.len()));
+        self.accessors.push(Accessor {
+            component: FLOAT,
+            kind,
+            count,
+            target: ARRAY_BUFFER,
+            offset,
+            length: self.data.len() - offset,
+            min,
+            max,
+        });
+        Ok(())
+    }
+
+    fn push_indices(&mut self, indices: &[u32], vertex_count: usize) {
+        let offset = self.data.len();
+        // the largest value of an index type is reserved for primitive restart
+        let component = if vertex_count <= u16::MAX as usize {
+            for &i in indices {
+                self.data.extend_from_slice(&(i as u16).to_le_bytes());
+            }
+            UNSIGNED_SHORT
+        } else {
+            for &i in indices {
+                self.data.extend_from_slice(&i.to_le_bytes());
+            }
+            UNSIGNED_INT
+        };
+        let min = indices.iter().min().map_or(0, |&i| i) as f64;
+        let max = indices.iter().max().map_or(0, |&i| i) as f64;
+        self.accessors.push(Accessor {
+            component,
+            kind: "SCALAR",
+            count: indices.len(),
+            target: ELEMENT_ARRAY_BUFFER,
+            offset,
+            length: self.data.len() - offset,
+            min: vec![min],
+            max: vec![max],
+        });
+        self.data.resize((self.data.len() + 3) & !3, 0);
+    }
+}
+
+impl GltfWriter {
+    /// Creates a new `GltfWriter` that writes every attribute the vertices
+    /// have.
+    pub fn new() -> Self {
+        GltfWriter {
+            name: None,
+            normals: true,
+            tex_coords: true,
+            tangents: true,
+        }
+    }
+
+    /// Sets the name of the written mesh.
+    pub fn name(self, name: &str) -> Self {
+        GltfWriter {
+            name: Some(name.to_owned()),
+            ..self
+        }
+    }
+
+    /// Sets whether normals are written.
+    pub fn normals(self, normals: bool) -> Self {
+        GltfWriter { normals, ..self }
+    }
+
+    /// Sets whether texture coordinates are written.
+    pub fn tex_coords(self, tex_coords: bool) -> Self {
+        GltfWriter { tex_coords, ..self }
+    }
+
+    /// Sets whether tangents are written.
+    pub fn tangents(self, tangents: bool) -> Self {
+        GltfWriter { tangents, ..self }
+    }
+
+    /// Writes an indexed mesh as `.gltf` JSON into `json` and its buffer
+    /// into `bin`, which the JSON refers to as `bin_uri`.
+    ///
+    /// Polygons are triangulated as they are written. An empty mesh is an
+    /// error of kind `InvalidInput`, as glTF does not allow empty accessors.
+    pub fn write_gltf<J, B, V, P>(
+        &self,
+        mut json: J,
+        mut bin: B,
+        bin_uri: &str,
+        vertices: &[V],
+        polygons: &[P],
+    ) -> io::Result<()>
+    where
+        J: Write,
+        B: Write,
+        V: VertexAttributes,
+        P: EmitTriangles<Vertex = usize>,
+    {
+        let buffers = self.buffers(vertices, polygons)?;
+        json.write_all(self.json(&buffers, Some(bin_uri)).as_bytes())?;
+        bin.write_all(&buffers.data)
+    }
+
+    /// Writes an indexed mesh as a single binary `.glb` file.
+    pub fn write_glb<W, V, P>(&self, mut out: W, vertices: &[V], polygons: &[P]) -> io::Result<()>
+    where
+        W: Write,
+        V: VertexAttributes,
+        P: EmitTriangles<Vertex = usize>,
+    {
+        let buffers = self.buffers(vertices, polygons)?;
+        let mut json = self.json(&buffers, None).into_bytes();
+        json.resize((json.len() + 3) & !3, b' ');
+
+        let length = 12 + 8 + json.len() + 8 + buffers.data.len();
+        out.write_all(GLB_MAGIC)?;
+        out.write_all(&2u32.to_le_bytes())?;
+        out.write_all(&(length as u32).to_le_bytes())?;
+        out.write_all(&(json.len() as u32).to_le_bytes())?;
+        out.write_all(GLB_JSON)?;
+        out.write_all(&json)?;
+        out.write_all(&(buffers.data.len() as u32).to_le_bytes())?;
+        out.write_all(GLB_BIN)?;
+        out.write_all(&buffers.data)
+    }
+
+    /// Saves an indexed mesh to `path`. A path ending in `.glb` gets a
+    /// binary file, anything else gets JSON along with a `.bin` file of
+    /// the same name next to it. A path ending in `.bin` is rejected.
+    pub fn save<Q, V, P>(&self, path: Q, vertices: &[V], polygons: &[P]) -> io::Result<()>
+    where
+        Q: AsRef<Path>,
+        V: VertexAttributes,
+        P: EmitTriangles<Vertex = usize>,
+    {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|e| e == "bin") {
+            // the buffer would be written over the JSON
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a .bin path is reserved for the buffer",
+            ));
+        }
+        if path.extension().is_some_and(|e| e == "glb") {
+            let mut out = BufWriter::new(File::create(path)?);
+            self.write_glb(&mut out, vertices, polygons)?;
+            return out.flush();
+        }
+
+        let bin_path = path.with_extension("bin");
+        let bin_uri = bin_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid file name"))?;
+        let mut json = BufWriter::new(File::create(path)?);
+        let mut bin = BufWriter::new(File::create(&bin_path)?);
+        self.write_gltf(&mut json, &mut bin, bin_uri, vertices, polygons)?;
+        json.flush()?;
+        bin.flush()
+    }
+
+    fn buffers<V, P>(&self, vertices: &[V], polygons: &[P]) -> io::Result<Buffers>
+    where
+        V: VertexAttributes,
+        P: EmitTriangles<Vertex = usize>,
+    {
+        let mut indices = Vec::new();
+        for p in polygons {
+            p.emit_triangles(|t| indices.extend_from_slice(&[t.x, t.y, t.z]));
+        }
+        if vertices.is_empty() || indices.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "glTF meshes can not be empty",
+            ));
+        }
+        if vertices.len() > u32::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "too many vertices for 32-bit indices",
+            ));
+        }
+        if indices.iter().any(|&i| i >= vertices.len()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "polygon index out of bounds",
+            ));
+        }
+
+        let mut buffers = Buffers {
+            data: Vec::new(),
+            accessors: Vec::new(),
+            attributes: Vec::new(),
+        };
+        buffers.push_floats(
+            "POSITION",
+            "VEC3",
+            vertices.iter().map(|v| {
+                let p = v.position();
+                vec![p.x, p.y, p.z]
+            }),
+        )?;
+        if self.normals && vertices.iter().all(|v| v.normal().is_some()) {
+            buffers.push_floats(
+                "NORMAL",
+                "VEC3",
+                vertices.iter().map(|v| {
+                    let n = v.normal().unwrap();
+                    vec![n.x, n.y, n.z]
+                }),
+            )?;
+        }
+        if self.tangents && vertices.iter().all(|v| v.tangent().is_some()) {
+            buffers.push_floats(
+                "TANGENT",
+                "VEC4",
+                vertices.iter().map(|v| {
+                    let t = v.tangent().unwrap();
+                    vec![t.x, t.y, t.z, t.w]
+                }),
+            )?;
+        }
+        if self.tex_coords && vertices.iter().all(|v| v.tex_coord().is_some()) {
+            buffers.push_floats(
+                "TEXCOORD_0",
+                "VEC2",
+                vertices.iter().map(|v| {
+                    let t = v.tex_coord().unwrap();
+                    vec![t.x, t.y]
+                }),
+            )?;
+        }
+        let indices: Vec<u32> = indices.into_iter().map(|i| i as u32).collect();
+        buffers.push_indices(&indices, vertices.len());
+        Ok(buffers)
+    }
+
+    fn json(&self, buffers: &Buffers, uri: Option<&str>) -> String {
+        // writing into a `String` can not fail
+        let mut s = String::new();
+        s.push_str("{\"asset\":{\"version\":\"2.0\",\"generator\":\"genmesh\"},");
+        s.push_str("\"scene\":0,\"scenes\":[{\"nodes\":[0]}],\"nodes\":[{\"mesh\":0}],");
+
+        s.push_str("\"meshes\":[{");
+        if let Some(name) = &self.name {
+            let _ = write!(s, "\"name\":{},", quote(name));
+        }
+        s.push_str("\"primitives\":[{\"attributes\":{");
+        for (i, (semantic, accessor)) in buffers.attributes.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(s, "{}\"{}\":{}", separator, semantic, accessor);
+        }
+        let _ = write!(
+            s,
+            "}},\"indices\":{},\"mode\":4}}]}}],",
+            buffers.accessors.len() - 1
+        );
+
+        s.push_str("\"accessors\":[");
+        for (i, a) in buffers.accessors.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(
+                s,
+                "{}{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"{}\",\"min\":{},\"max\":{}}}",
+                separator,
+                i,
+                a.component,
+                a.count,
+                a.kind,
+                numbers(&a.min),
+                numbers(&a.max),
+            );
+        }
+        s.push_str("],\"bufferViews\":[");
+        for (i, a) in buffers.accessors.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(
+                s,
+                "{}{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{},\"target\":{}}}",
+                separator, a.offset, a.length, a.target,
+            );
+        }
+        let _ = write!(s, "],\"buffers\":[{{\"byteLength\":{}", buffers.data.len());
+        if let Some(uri) = uri {
+            let _ = write!(s, ",\"uri\":{}", quote(uri));
+        }
+        s.push_str("}]}");
+        s
+    }
+}
+
+// a JSON array of numbers, already checked to be finite
+fn numbers(values: &[f64]) -> String {
+    let values: Vec<String> = values.iter().map(|&v| format!("{}", v as f32)).collect();
+    format!("[{}]", values.join(","))
+}
+
+// a JSON string literal
+fn quote(text: &str) -> String {
+    let mut s = String::with_capacity(text.len() + 2);
+    s.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(s, "\\u{:04x}", c as u32);
+            }
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+    s
+}
@@ -0,0 +1,609 @@
+//! A half-edge mesh, for ordered adjacency queries and local edits.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::{EmitVertices, Line, Polygon, Quad, Triangle};
+
+/// The reasons a [`HalfEdgeMesh`] can not be built.
+///
+/// [`HalfEdgeMesh`]: struct.HalfEdgeMesh.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HalfEdgeError {
+    /// A polygon uses the same vertex twice, or has fewer than three
+    /// vertices. Holds the index of the polygon.
+    DegenerateFace(usize),
+    /// An edge is shared by more than two faces, or by two faces that
+    /// are wound in opposite directions. Holds the directed edge that
+    /// was seen twice.
+    NonManifoldEdge(Line<usize>),
+    /// The faces around a vertex form more than one fan. Holds the
+    /// index of the vertex.
+    NonManifoldVertex(usize),
+}
+
+impl fmt::Display for HalfEdgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HalfEdgeError::DegenerateFace(face) => write!(f, "face {} is degenerate", face),
+            HalfEdgeError::NonManifoldEdge(edge) => {
+                write!(f, "edge {} -> {} is not manifold", edge.x, edge.y)
+            }
+            HalfEdgeError::NonManifoldVertex(vertex) => {
+                write!(f, "vertex {} is not manifold", vertex)
+            }
+        }
+    }
+}
+
+impl Error for HalfEdgeError {}
+
+#[derive(Clone, Copy, Debug)]
+struct HalfEdge {
+    // the vertex the half-edge points to
+    vertex: usize,
+    face: Option<usize>,
+    next: usize,
+    prev: usize,
+    twin: usize,
+    removed: bool,
+}
+
+/// A polygon mesh stored as half-edges, built from a vertex buffer and
+/// indexed triangles or quads.
+///
+/// Every edge is made of two half-edges pointing in opposite directions,
+/// one for each side. The half-edges of a face run counter-clockwise around
+/// it, while half-edges on the boundary have no face and run around the
+/// holes of the mesh instead. Vertices, faces and half-edges are addressed
+/// by index, and indices stay stable across edits: removed elements are
+/// only marked as such, until the mesh is turned back into buffers with
+/// [`into_indexed`].
+///
+/// [`into_indexed`]: #method.into_indexed
+#[derive(Clone, Debug)]
+pub struct HalfEdgeMesh<T> {
+    vertices: Vec<T>,
+    half_edges: Vec<HalfEdge>,
+    // an outgoing half-edge of every vertex, the boundary one if any
+    vertex_edge: Vec<Option<usize>>,
+    // a half-edge of every face
+    face_edge: Vec<Option<usize>>,
+}
+
+impl<T> HalfEdgeMesh<T> {
+    /// Builds a half-edge mesh from a vertex buffer and polygons indexing
+    /// into it. The polygons must form a consistently oriented manifold,
+    /// possibly with boundaries.
+    pub fn new<P>(vertices: Vec<T>, polygons: &[P]) -> Result<Self, HalfEdgeError>
+    where
+        P: EmitVertices<usize> + Clone,
+    {
+        let mut mesh = HalfEdgeMesh {
+            vertex_edge: vec![None; vertices.len()],
+            vertices,
+            half_edges: Vec::new(),
+            face_edge: Vec::with_capacity(polygons.len()),
+        };
+        // the half-edge of every directed edge
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+
+        for (f, p) in polygons.iter().enumerate() {
+            let mut face = Vec::with_capacity(4);
+            p.clone().emit_vertices(|v| face.push(v));
+            let distinct = face.iter().enumerate().all(|(i, v)| !face[..i].contains(v));
+            if face.len() < 3 || !distinct {
+                return Err(HalfEdgeError::DegenerateFace(f));
+            }
+
+            let mut ring = Vec::with_capacity(face.len());
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+                let h = match edges.get(&(a, b)) {
+                    Some(&h) if mesh.half_edges[h].face.is_some() => {
+                        return Err(HalfEdgeError::NonManifoldEdge(Line::new(a, b)));
+                    }
+                    Some(&h) => h,
+                    None => {
+                        let h = mesh.add_edge(a, b);
+                        edges.insert((a, b), h);
+                        edges.insert((b, a), h + 1);
+                        h
+                    }
+                };
+                mesh.half_edges[h].face = Some(f);
+                mesh.vertex_edge[a] = Some(h);
+                ring.push(h);
+            }
+            for (i, &h) in ring.iter().enumerate() {
+                mesh.link(h, ring[(i + 1) % ring.len()]);
+            }
+            mesh.face_edge.push(Some(ring[0]));
+        }
+
+        // link the boundary half-edges into loops
+        let mut boundary_out: Vec<Option<usize>> = vec![None; mesh.vertices.len()];
+        for h in 0..mesh.half_edges.len() {
+            if mesh.half_edges[h].face.is_none() {
+                let v = mesh.origin(h);
+                if boundary_out[v].replace(h).is_some() {
+                    return Err(HalfEdgeError::NonManifoldVertex(v));
+                }
+                mesh.vertex_edge[v] = Some(h);
+            }
+        }
+        for h in 0..mesh.half_edges.len() {
+            if mesh.half_edges[h].face.is_none() {
+                let next = boundary_out[mesh.target(h)].unwrap();
+                mesh.link(h, next);
+            }
+        }
+
+        // every outgoing half-edge must be reachable from the one stored
+        let mut outgoing = vec![0; mesh.vertices.len()];
+        for h in 0..mesh.half_edges.len() {
+            outgoing[mesh.origin(h)] += 1;
+        }
+        for (v, &count) in outgoing.iter().enumerate() {
+            if mesh.vertex_edge[v].is_some() && mesh.outgoing(v).count() != count {
+                return Err(HalfEdgeError::NonManifoldVertex(v));
+            }
+        }
+
+        Ok(mesh)
+    }
+
+    /// Returns the data of vertex `v`.
+    pub fn vertex(&self, v: usize) -> &T {
+        &self.vertices[v]
+    }
+
+    /// Returns the data of vertex `v` for modification.
+    pub fn vertex_mut(&mut self, v: usize) -> &mut T {
+        &mut self.vertices[v]
+    }
+
+    /// Iterates over the indices of the vertices that are used by a face.
+    pub fn vertex_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.vertices.len()).filter(move |&v| self.vertex_edge[v].is_some())
+    }
+
+    /// Iterates over the indices of the faces that are not removed.
+    pub fn face_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.face_edge.len()).filter(move |&f| self.face_edge[f].is_some())
+    }
+
+    /// Iterates over the indices of the half-edges that are not removed.
+    pub fn half_edge_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.half_edges.len()).filter(move |&h| !self.half_edges[h].removed)
+    }
+
+    /// Returns the vertex half-edge `h` starts from.
+    pub fn origin(&self, h: usize) -> usize {
+        self.target(self.twin(h))
+    }
+
+    /// Returns the vertex half-edge `h` points to.
+    pub fn target(&self, h: usize) -> usize {
+        self.half_edges[h].vertex
+    }
+
+    /// Returns the half-edge running the other way along the same edge.
+    pub fn twin(&self, h: usize) -> usize {
+        self.half_edges[h].twin
+    }
+
+    /// Returns the half-edge that follows `h` around its face or hole.
+    pub fn next(&self, h: usize) -> usize {
+        self.half_edges[h].next
+    }
+
+    /// Returns the half-edge that precedes `h` around its face or hole.
+    pub fn prev(&self, h: usize) -> usize {
+        self.half_edges[h].prev
+    }
+
+    /// Returns the face of half-edge `h`, or `None` on the boundary.
+    pub fn face(&self, h: usize) -> Option<usize> {
+        self.half_edges[h].face
+    }
+
+    /// Returns a half-edge of face `f`.
+    pub fn face_half_edge(&self, f: usize) -> usize {
+        self.face_edge[f].expect("face is removed")
+    }
+
+    /// Returns an outgoing half-edge of vertex `v`, the one on the
+    /// boundary if the vertex has one. This is `None` for vertices that no
+    /// face uses.
+    pub fn vertex_half_edge(&self, v: usize) -> Option<usize> {
+        self.vertex_edge[v]
+    }
+
+    /// Returns the half-edge from `a` to `b`, if there is one.
+    pub fn find_half_edge(&self, a: usize, b: usize) -> Option<usize> {
+        self.outgoing(a).find(|&h| self.target(h) == b)
+    }
+
+    /// Returns true if vertex `v` lies on the boundary.
+    pub fn is_boundary_vertex(&self, v: usize) -> bool {
+        self.vertex_edge[v].is_some_and(|h| self.face(h).is_none())
+    }
+
+    /// Returns true if the edge of half-edge `h` lies on the boundary.
+    pub fn is_boundary_edge(&self, h: usize) -> bool {
+        self.face(h).is_none() || self.face(self.twin(h)).is_none()
+    }
+
+    /// Iterates over the half-edges leaving vertex `v`, counter-clockwise.
+    /// On the boundary, the iteration starts with the boundary half-edge.
+    pub fn outgoing(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        let start = self.vertex_edge[v];
+        std::iter::successors(start, move |&h| {
+            let h = self.twin(self.prev(h));
+            if Some(h) == start {
+                None
+            } else {
+                Some(h)
+            }
+        })
+    }
+
+    /// Iterates over the vertices around vertex `v`, counter-clockwise.
+    pub fn one_ring(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.outgoing(v).map(move |h| self.target(h))
+    }
+
+    /// Iterates over the faces around vertex `v`, counter-clockwise.
+    pub fn vertex_faces(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.outgoing(v).filter_map(move |h| self.face(h))
+    }
+
+    /// Iterates over the half-edges of the loop that `h` is part of.
+    pub fn loop_half_edges(&self, h: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(h), move |&e| {
+            let e = self.next(e);
+            if e == h {
+                None
+            } else {
+                Some(e)
+            }
+        })
+    }
+
+    /// Iterates over the vertices of face `f`, counter-clockwise.
+    pub fn face_vertices(&self, f: usize) -> impl Iterator<Item = usize> + '_ {
+        self.loop_half_edges(self.face_half_edge(f))
+            .map(move |h| self.origin(h))
+    }
+
+    /// Returns the vertices of every hole of the mesh, in the order the
+    /// boundary half-edges run around them.
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.half_edges.len()];
+        let mut loops = Vec::new();
+        for h in self.half_edge_indices() {
+            if self.face(h).is_some() || visited[h] {
+                continue;
+            }
+            let ring = self
+                .loop_half_edges(h)
+                .map(|e| {
+                    visited[e] = true;
+                    self.origin(e)
+                })
+                .collect();
+            loops.push(ring);
+        }
+        loops
+    }
+
+    /// Flips the edge of half-edge `h`, so that it connects the two
+    /// vertices opposite to it instead.
+    ///
+    /// Returns false and leaves the mesh untouched if the edge lies on the
+    /// boundary, if either face is not a triangle, or if the two opposite
+    /// vertices are connected already.
+    pub fn flip_edge(&mut self, h: usize) -> bool {
+        let t = self.twin(h);
+        let (f1, f2) = match (self.face(h), self.face(t)) {
+            (Some(f1), Some(f2)) if self.is_triangle(h) && self.is_triangle(t) => (f1, f2),
+            _ => return false,
+        };
+        let (h1, h2) = (self.next(h), self.prev(h));
+        let (t1, t2) = (self.next(t), self.prev(t));
+        let (a, b) = (self.origin(h), self.target(h));
+        let (c, d) = (self.target(h1), self.target(t1));
+        if self.find_half_edge(c, d).is_some() {
+            return false;
+        }
+
+        // the triangles (a, b, c) and (b, a, d) become (c, a, d) and (d, b, c)
+        self.half_edges[h].vertex = c;
+        self.half_edges[t].vertex = d;
+        self.set_face(f1, &[h2, t1, h]);
+        self.set_face(f2, &[t2, h1, t]);
+
+        if self.vertex_edge[a] == Some(h) {
+            self.vertex_edge[a] = Some(t1);
+        }
+        if self.vertex_edge[b] == Some(t) {
+            self.vertex_edge[b] = Some(h1);
+        }
+        true
+    }
+
+    /// Splits the edge of half-edge `h` at a new vertex, and splits the
+    /// triangles on either side of it in two. Returns the index of the new
+    /// vertex, or `None` if a face beside the edge is not a triangle.
+    pub fn split_edge(&mut self, h: usize, vertex: T) -> Option<usize> {
+        let t = self.twin(h);
+        if (self.face(h).is_some() && !self.is_triangle(h))
+            || (self.face(t).is_some() && !self.is_triangle(t))
+        {
+            return None;
+        }
+        let b = self.target(h);
+        let m = self.vertices.len();
+        self.vertices.push(vertex);
+        self.vertex_edge.push(None);
+
+        // a -> b becomes a -> m -> b, and b -> a becomes b -> m -> a
+        let (hn, tp) = (self.next(h), self.prev(t));
+        let n = self.add_edge(m, b);
+        self.half_edges[h].vertex = m;
+        self.half_edges[n].face = self.face(h);
+        self.half_edges[n + 1].face = self.face(t);
+        self.link(h, n);
+        self.link(n, hn);
+        self.link(tp, n + 1);
+        self.link(n + 1, t);
+
+        if self.vertex_edge[b] == Some(t) {
+            self.vertex_edge[b] = Some(n + 1);
+        }
+        self.vertex_edge[m] = Some(if self.face(t).is_none() { t } else { n });
+
+        if self.face(n).is_some() {
+            self.cut_corner(n);
+        }
+        if self.face(t).is_some() {
+            self.cut_corner(t);
+        }
+        Some(m)
+    }
+
+    /// Collapses the edge of half-edge `h`, merging the vertex it points
+    /// to into the vertex it starts from, and removing the triangles on
+    /// either side of it. The data of the remaining vertex is left as it
+    /// is, so move it with [`vertex_mut`] if needed.
+    ///
+    /// Returns false and leaves the mesh untouched if the collapse would
+    /// make the mesh non-manifold, or if a face beside the edge is not a
+    /// triangle.
+    ///
+    /// [`vertex_mut`]: #method.vertex_mut
+    pub fn collapse_edge(&mut self, h: usize) -> bool {
+        let t = self.twin(h);
+        let (a, b) = (self.origin(h), self.target(h));
+
+        // the vertex opposite to the edge in each face
+        let mut opposite = Vec::with_capacity(2);
+        for &e in &[h, t] {
+            if self.face(e).is_some() {
+                let (e1, e2) = (self.next(e), self.prev(e));
+                if !self.is_triangle(e)
+                    || (self.face(self.twin(e1)).is_none() && self.face(self.twin(e2)).is_none())
+                {
+                    return false;
+                }
+                opposite.push(self.target(e1));
+            }
+        }
+        if !self.is_boundary_edge(h) && self.is_boundary_vertex(a) && self.is_boundary_vertex(b) {
+            return false;
+        }
+        // the link condition: the only vertices the two ends share are
+        // the ones opposite to the edge
+        let ring_a: Vec<usize> = self.one_ring(a).collect();
+        if self
+            .one_ring(b)
+            .any(|v| ring_a.contains(&v) && !opposite.contains(&v))
+        {
+            return false;
+        }
+
+        let incoming: Vec<usize> = self.outgoing(b).map(|e| self.twin(e)).collect();
+        let mut keep = None;
+
+        for &e in &[h, t] {
+            let (e1, e2) = (self.next(e), self.prev(e));
+            match self.face(e) {
+                Some(f) => {
+                    // (a, b, c) goes away, and c -> b becomes c -> a
+                    let (o1, o2) = (self.twin(e1), self.twin(e2));
+                    self.half_edges[o1].twin = o2;
+                    self.half_edges[o2].twin = o1;
+                    let c = self.target(e1);
+                    if self.vertex_edge[c] == Some(e2) {
+                        self.vertex_edge[c] = Some(o1);
+                    }
+                    self.half_edges[e1].removed = true;
+                    self.half_edges[e2].removed = true;
+                    self.face_edge[f] = None;
+                    keep = keep.or(Some(o2));
+                }
+                None => {
+                    self.link(e2, e1);
+                    keep = keep.or(Some(e1));
+                }
+            }
+        }
+        self.half_edges[h].removed = true;
+        self.half_edges[t].removed = true;
+
+        for e in incoming {
+            if !self.half_edges[e].removed {
+                self.half_edges[e].vertex = a;
+            }
+        }
+        self.vertex_edge[b] = None;
+
+        // pick an outgoing half-edge for the kept vertex, preferring the
+        // boundary one
+        let start = match self.vertex_edge[a] {
+            Some(e) if !self.half_edges[e].removed => e,
+            _ => match keep {
+                Some(e) if self.origin(e) == a => e,
+                Some(e) => self.twin(e),
+                None => unreachable!(),
+            },
+        };
+        self.vertex_edge[a] = Some(start);
+        for v in std::iter::once(a).chain(opposite) {
+            let boundary = self.outgoing(v).find(|&e| self.face(e).is_none());
+            if boundary.is_some() {
+                self.vertex_edge[v] = boundary;
+            }
+        }
+        true
+    }
+
+    /// Checks that the connectivity of the mesh is consistent. This is
+    /// always true for meshes built and edited through this type, and is
+    /// meant for tests.
+    pub fn is_valid(&self) -> bool {
+        let live = |h: usize| h < self.half_edges.len() && !self.half_edges[h].removed;
+        for h in self.half_edge_indices() {
+            let e = &self.half_edges[h];
+            if !live(e.twin) || !live(e.next) || !live(e.prev) {
+                return false;
+            }
+            if e.twin == h
+                || self.twin(e.twin) != h
+                || self.prev(e.next) != h
+                || self.next(e.prev) != h
+                || self.face(e.next) != e.face
+                || self.target(e.prev) != self.origin(h)
+                || (e.face.is_none() && self.face(e.twin).is_none())
+            {
+                return false;
+            }
+            if let Some(f) = e.face {
+                if self.face_edge.get(f).copied().flatten().is_none() {
+                    return false;
+                }
+            }
+        }
+        for f in self.face_indices() {
+            let h = self.face_edge[f].unwrap();
+            if !live(h) || self.face(h) != Some(f) {
+                return false;
+            }
+            let len = self
+                .loop_half_edges(h)
+                .take(self.half_edges.len() + 1)
+                .count();
+            if !(3..=4).contains(&len) {
+                return false;
+            }
+        }
+        let mut outgoing = vec![0; self.vertices.len()];
+        for h in self.half_edge_indices() {
+            outgoing[self.origin(h)] += 1;
+        }
+        for (v, &count) in outgoing.iter().enumerate() {
+            match self.vertex_edge[v] {
+                Some(h) if live(h) && self.origin(h) == v => {
+                    let boundary = self.outgoing(v).any(|e| self.face(e).is_none());
+                    if self.outgoing(v).take(count + 1).count() != count
+                        || (boundary && self.face(h).is_some())
+                    {
+                        return false;
+                    }
+                }
+                None if count == 0 => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Turns the mesh back into a vertex buffer and polygons indexing into
+    /// it. Vertices that no face uses are dropped.
+    pub fn into_indexed(self) -> (Vec<T>, Vec<Polygon<usize>>) {
+        let mut remap = vec![usize::MAX; self.vertices.len()];
+        for (i, v) in self.vertex_indices().enumerate() {
+            remap[v] = i;
+        }
+
+        let polygons = self
+            .face_indices()
+            .map(|f| {
+                let face: Vec<usize> = self.face_vertices(f).map(|v| remap[v]).collect();
+                match face[..] {
+                    [x, y, z] => Polygon::PolyTri(Triangle::new(x, y, z)),
+                    [x, y, z, w] => Polygon::PolyQuad(Quad::new(x, y, z, w)),
+                    _ => unreachable!(),
+                }
+            })
+            .collect();
+        let vertices = self
+            .vertices
+            .into_iter()
+            .zip(self.vertex_edge)
+            .filter_map(|(v, e)| e.map(|_| v))
+            .collect();
+        (vertices, polygons)
+    }
+
+    // adds the half-edges a -> b and b -> a, returning the first
+    fn add_edge(&mut self, a: usize, b: usize) -> usize {
+        let h = self.half_edges.len();
+        let half_edge = |vertex, twin| HalfEdge {
+            vertex,
+            face: None,
+            next: twin,
+            prev: twin,
+            twin,
+            removed: false,
+        };
+        self.half_edges.push(half_edge(b, h + 1));
+        self.half_edges.push(half_edge(a, h));
+        h
+    }
+
+    fn link(&mut self, h: usize, next: usize) {
+        self.half_edges[h].next = next;
+        self.half_edges[next].prev = h;
+    }
+
+    fn is_triangle(&self, h: usize) -> bool {
+        self.next(self.next(self.next(h))) == h
+    }
+
+    fn set_face(&mut self, f: usize, ring: &[usize]) {
+        for (i, &h) in ring.iter().enumerate() {
+            self.half_edges[h].face = Some(f);
+            self.link(h, ring[(i + 1) % ring.len()]);
+        }
+        self.face_edge[f] = Some(ring[0]);
+    }
+
+    // splits the quad made by splitting an edge of a triangle, from the
+    // new vertex at the start of `h` to the corner opposite to it
+    fn cut_corner(&mut self, h: usize) {
+        let f = self.face(h).unwrap();
+        let x = self.next(h);
+        let y = self.next(x);
+        let back = self.next(y);
+        let (m, c) = (self.origin(h), self.target(x));
+
+        let d = self.add_edge(c, m);
+        let g = self.face_edge.len();
+        self.face_edge.push(None);
+        self.set_face(f, &[h, x, d]);
+        self.set_face(g, &[d + 1, y, back]);
+    }
+}
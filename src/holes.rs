@@ -0,0 +1,149 @@
+//! Detection and filling of holes in indexed meshes.
+
+use crate::math::Vector3;
+use crate::{EmitVertices, Position, TopologyReport, Triangle};
+
+/// How a [`HoleFiller`] triangulates a hole.
+///
+/// [`HoleFiller`]: struct.HoleFiller.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillMethod {
+    /// A fan of triangles around the first vertex of the hole. This is
+    /// cheap, and fine for small or convex holes.
+    Fan,
+    /// The triangulation with the least total area, which follows the
+    /// shape of curved and concave holes much better. This takes time
+    /// cubic in the size of the hole.
+    MinimumArea,
+}
+
+/// Finds the holes of an indexed mesh and triangulates them.
+///
+/// A hole is a loop of edges that belong to a single face each, as listed
+/// by [`TopologyReport::boundary_loops`]. The new triangles are wound to
+/// match the faces around the hole.
+///
+/// ```
+/// use genmesh::generators::{IcoSphere, IndexedPolygon, SharedVertex};
+/// use genmesh::{HoleFiller, TopologyReport};
+///
+/// let sphere = IcoSphere::new();
+/// let vertices: Vec<_> = sphere.shared_vertex_iter().collect();
+/// // leave out the first triangle
+/// let mut triangles: Vec<_> = sphere.indexed_polygon_iter().skip(1).collect();
+///
+/// triangles.extend(HoleFiller::new().fill(&vertices, &triangles, |v| v.pos));
+/// assert!(TopologyReport::new(triangles).is_closed());
+/// ```
+///
+/// [`TopologyReport::boundary_loops`]: struct.TopologyReport.html#structfield.boundary_loops
+#[derive(Clone, Copy, Debug)]
+pub struct HoleFiller {
+    method: FillMethod,
+    max_edges: usize,
+}
+
+impl Default for HoleFiller {
+    fn default() -> Self {
+        HoleFiller::new()
+    }
+}
+
+impl HoleFiller {
+    /// Creates a new `HoleFiller` that fills holes of any size with
+    /// minimum area triangulations.
+    pub fn new() -> Self {
+        HoleFiller {
+            method: FillMethod::MinimumArea,
+            max_edges: usize::MAX,
+        }
+    }
+
+    /// Sets how the holes are triangulated.
+    pub fn method(self, method: FillMethod) -> Self {
+        HoleFiller { method, ..self }
+    }
+
+    /// Sets the largest number of edges around a hole that is filled.
+    /// Larger holes, like the rim of an uncapped cylinder, are left alone.
+    pub fn max_edges(self, max_edges: usize) -> Self {
+        HoleFiller { max_edges, ..self }
+    }
+
+    /// Returns the triangles that fill the holes of the mesh made of
+    /// `polygons`. Append them to the mesh to close it.
+    ///
+    /// `f` looks up the position of a vertex.
+    pub fn fill<T, P, F>(&self, vertices: &[T], polygons: &[P], mut f: F) -> Vec<Triangle<usize>>
+    where
+        P: EmitVertices<usize> + Clone,
+        F: FnMut(&T) -> Position,
+    {
+        let report = TopologyReport::new(polygons.iter().cloned());
+        let mut triangles = Vec::new();
+        for hole in report.boundary_loops {
+            if hole.len() > self.max_edges {
+                continue;
+            }
+            // the faces run along the loop, so the filling runs against it
+            let hole: Vec<usize> = hole.into_iter().rev().collect();
+            match self.method {
+                FillMethod::Fan => {
+                    for i in 1..hole.len() - 1 {
+                        triangles.push(Triangle::new(hole[0], hole[i], hole[i + 1]));
+                    }
+                }
+                FillMethod::MinimumArea => {
+                    let positions: Vec<Vector3> = hole
+                        .iter()
+                        .map(|&v| Vector3::from(f(&vertices[v])))
+                        .collect();
+                    minimum_area(&positions, |a, b, c| {
+                        triangles.push(Triangle::new(hole[a], hole[b], hole[c]))
+                    });
+                }
+            }
+        }
+        triangles
+    }
+}
+
+// triangulates a polygon with the least total area by dynamic programming
+// over its sub-polygons, emitting triangles of indices into `points`
+fn minimum_area<F: FnMut(usize, usize, usize)>(points: &[Vector3], mut emit: F) {
+    let n = points.len();
+    let area = |a: usize, b: usize, c: usize| {
+        (points[b] - points[a])
+            .cross(points[c] - points[a])
+            .magnitude()
+    };
+
+    // best[i][j] is the least area of the polygon from i to j, and split
+    // holds the vertex its triangle on the edge from i to j uses
+    let mut best = vec![vec![0f32; n]; n];
+    let mut split = vec![vec![0; n]; n];
+    for len in 2..n {
+        for i in 0..n - len {
+            let j = i + len;
+            best[i][j] = f32::INFINITY;
+            for k in i + 1..j {
+                let cost = best[i][k] + best[k][j] + area(i, k, j);
+                if cost < best[i][j] {
+                    best[i][j] = cost;
+                    split[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let mut stack = vec![(0, n - 1)];
+    while let Some((i, j)) = stack.pop() {
+        if j - i < 2 {
+            continue;
+        }
+        let k = split[i][j];
+        emit(i, k, j);
+        stack.push((i, k));
+        stack.push((k, j));
+    }
+}
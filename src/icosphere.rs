@@ -1,9 +1,7 @@
 //! Icosahedral sphere
 
-use std::collections::HashMap;
-
 use crate::generators::{IndexedPolygon, SharedVertex};
-use crate::{math::Vector3, Triangle, Vertex};
+use crate::{math::Vector3, midpoint_subdivide, Triangle, Vertex};
 
 /// Icosahedral sphere with radius 1, centered at (0., 0., 0.).
 #[derive(Clone, Debug)]
@@ -22,8 +20,8 @@ pub struct IcoSphere {
 // get a unit sphere, therefore we need to normalize the vector (0, 1, (1 + sqrt(5)) / 2). This
 // gives us the values below. These values are the half dimensions of the orthogonal rectangles
 // from which we get the corner points that define a unit icosahedral sphere.
-const T: f32 = 0.85065080835204;
-const X: f32 = 0.5257311121191336;
+const T: f32 = 0.850_650_8;
+const X: f32 = 0.525_731_1;
 
 const VERTICES: [[f32; 3]; 12] = [
     // corners of the rectangle in the XY plane
@@ -88,10 +86,13 @@ impl IcoSphere {
     /// - `subdivides` is the number of subdivisions to perform
     pub fn subdivide(subdivides: usize) -> Self {
         let mut vertices = VERTICES.to_vec();
-        let mut faces = FACES.to_vec();
+        let mut faces: Vec<_> = FACES
+            .iter()
+            .map(|&[x, y, z]| Triangle::new(x, y, z))
+            .collect();
 
         for _ in 0..subdivides {
-            let (v, f) = subdivide_impl(vertices, faces);
+            let (v, f) = midpoint_subdivide(&vertices, &faces, midpoint, Some(&mut normalize));
             vertices = v;
             faces = f;
         }
@@ -99,7 +100,7 @@ impl IcoSphere {
         Self {
             i: 0,
             vertices,
-            faces,
+            faces: faces.iter().map(|t| [t.x, t.y, t.z]).collect(),
         }
     }
 
@@ -117,40 +118,16 @@ impl Default for IcoSphere {
     }
 }
 
-fn subdivide_impl(
-    mut vertices: Vec<[f32; 3]>,
-    faces: Vec<[usize; 3]>,
-) -> (Vec<[f32; 3]>, Vec<[usize; 3]>) {
-    let mut lookup = HashMap::<(usize, usize), usize>::default();
-    let mut new_faces = Vec::<[usize; 3]>::default();
-    for face in &faces {
-        let mut mid: [usize; 3] = [0; 3];
-        for i in 0..3 {
-            let pair = (face[i], face[(i + 1) % 3]);
-            // find new vertex on the edge
-            mid[i] = match lookup.get(&pair) {
-                Some(i) => *i,
-                None => vertices.len(),
-            };
-            if mid[i] == vertices.len() {
-                lookup.insert(pair, mid[i]);
-                lookup.insert((pair.1, pair.0), mid[i]);
-                let new = new_point(vertices[pair.0], vertices[pair.1]);
-                vertices.push(new);
-            }
-        }
-        new_faces.push([face[0], mid[0], mid[2]]);
-        new_faces.push([face[1], mid[1], mid[0]]);
-        new_faces.push([face[2], mid[2], mid[1]]);
-        new_faces.push([mid[0], mid[1], mid[2]]);
+fn midpoint(weights: &[(&[f32; 3], f32)]) -> [f32; 3] {
+    let mut out = Vector3::new(0., 0., 0.);
+    for &(&[x, y, z], w) in weights {
+        out += Vector3::new(x, y, z) * w;
     }
-    (vertices, new_faces)
+    out.into()
 }
 
-fn new_point(start: [f32; 3], end: [f32; 3]) -> [f32; 3] {
-    Vector3::new(start[0] + end[0], start[1] + end[1], start[2] + end[2])
-        .normalized()
-        .into()
+fn normalize([x, y, z]: [f32; 3]) -> [f32; 3] {
+    Vector3::new(x, y, z).normalized().into()
 }
 
 impl Iterator for IcoSphere {
@@ -22,16 +22,63 @@ pub use poly::{
 
 pub use triangulate::{EmitTriangles, Triangulate, TriangulateIterator};
 
+pub use attributes::VertexAttributes;
+
+pub use camera::{Camera, Projection};
+
+pub use error::ReadError;
+
+pub use components::{split_components, Component, MergedMesh};
+
+pub use halfedge::{HalfEdgeError, HalfEdgeMesh};
+
+pub use holes::{FillMethod, HoleFiller};
+
 pub use indexer::{Indexer, LruIndexer};
 
+pub use lod::{Lod, LodChain};
+
+pub use meshlet::{Meshlet, MeshletBuilder};
+
 pub use neighbors::Neighbors;
 
+pub use normals::{flat_shade, flat_shade_indexed, NormalWeight, ProvokingVertex, SmoothNormals};
+
+pub use simplify::Simplifier;
+
+pub use subdivide::{catmull_clark, loop_subdivide, midpoint_subdivide, Creases, EmitSplit};
+
+pub use topology::TopologyReport;
+
+pub use packing::{Attribute, AttributeLayout, Format, PackedVertices, VertexLayout};
+
+pub use orient::{orient_consistently, orient_outward, Flip, FlipWinding, FlipWindingIterator};
+
+pub use optimize::{
+    average_cache_miss_ratio, optimize_overdraw, optimize_vertex_cache, optimize_vertex_fetch,
+};
+
 mod math;
 
+mod attributes;
+mod camera;
+mod components;
+mod error;
 mod generator;
+mod halfedge;
+mod holes;
 mod indexer;
+mod lod;
+mod meshlet;
 mod neighbors;
+mod normals;
+mod optimize;
+mod orient;
+mod packing;
 mod poly;
+mod simplify;
+mod subdivide;
+mod topology;
 mod triangulate;
 
 mod circle;
@@ -59,10 +106,23 @@ pub mod generators {
     pub use super::torus::Torus;
 }
 
+pub mod encoding;
+pub mod gltf;
+pub mod obj;
+pub mod off;
+pub mod ply;
+pub mod raster;
+pub mod stl;
+pub mod svg;
+
 /// Common vertex position type.
 pub type Position = mint::Vector3<f32>;
 /// Common vertex normal type.
 pub type Normal = mint::Vector3<f32>;
+/// Common vertex texture coordinate type.
+pub type TexCoord = mint::Vector2<f32>;
+/// Common vertex tangent type, with the bitangent sign in `w`.
+pub type Tangent = mint::Vector4<f32>;
 /// Common vertex type.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vertex {
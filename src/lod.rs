@@ -0,0 +1,108 @@
+//! Chains of progressively simplified levels of detail.
+
+use std::ops::Range;
+
+use crate::generators::{IndexedPolygon, SharedVertex};
+use crate::{EmitTriangles, Position, Simplifier, Triangle};
+
+/// A single level of a [`LodChain`].
+///
+/// [`LodChain`]: struct.LodChain.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lod {
+    /// The range of `LodChain::triangles` that make up this level. The
+    /// matching range of a flattened index buffer is three times as large.
+    pub triangles: Range<usize>,
+    /// Error of this level relative to the source mesh, in the units of
+    /// the mesh. This is the sum of the [`Simplifier`] errors of every
+    /// step that led to this level.
+    ///
+    /// [`Simplifier`]: struct.Simplifier.html
+    pub error: f32,
+}
+
+/// A chain of levels of detail that all share a single vertex buffer.
+///
+/// Every level is simplified from the one before it, so the levels get
+/// coarser along the chain, and their errors accumulate.
+///
+/// ```
+/// use genmesh::generators::IcoSphere;
+/// use genmesh::{LodChain, Simplifier};
+///
+/// let sphere = IcoSphere::subdivide(3);
+/// let chain = LodChain::new(&sphere, Simplifier::default(), &[1., 0.5, 0.25, 0.125], |v| v.pos);
+/// assert_eq!(chain.levels.len(), 4);
+/// assert_eq!(chain.level(0).len(), 1280);
+/// assert!(chain.level(3).len() <= 160);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LodChain<V> {
+    /// The vertices shared by all levels.
+    pub vertices: Vec<V>,
+    /// The triangles of all levels, one after another.
+    pub triangles: Vec<Triangle<usize>>,
+    /// The levels, in the order they were requested.
+    pub levels: Vec<Lod>,
+}
+
+impl<V> LodChain<V> {
+    /// Builds a chain of levels of detail from an indexed mesh.
+    ///
+    /// # Arguments
+    ///
+    /// - `mesh` is the source mesh, its polygons are triangulated
+    /// - `simplifier` is the template every level is simplified with. Its
+    ///   target triangle count is ignored and replaced by the one of each
+    ///   level, so `Simplifier::default()` is a good starting point.
+    /// - `ratios` are the fractions of the source triangle count to aim for,
+    ///   one per level, in decreasing order. A level keeps more triangles
+    ///   than requested if its mesh can not be simplified any further.
+    /// - `f` looks up the position of a vertex.
+    pub fn new<G, P, F>(mesh: &G, simplifier: Simplifier, ratios: &[f32], mut f: F) -> Self
+    where
+        G: SharedVertex<V> + IndexedPolygon<P>,
+        P: EmitTriangles<Vertex = usize>,
+        F: FnMut(&V) -> Position,
+    {
+        let vertices: Vec<V> = mesh.shared_vertex_iter().collect();
+        let mut source = Vec::new();
+        for p in mesh.indexed_polygon_iter() {
+            p.emit_triangles(|t| source.push(t));
+        }
+
+        let mut triangles = Vec::new();
+        let mut levels = Vec::with_capacity(ratios.len());
+        let mut previous = source.clone();
+        let mut error = 0.;
+
+        for &ratio in ratios {
+            let target = (source.len() as f32 * ratio) as usize;
+            if target < previous.len() {
+                let (simplified, e) = simplifier
+                    .target_triangles(target)
+                    .simplify(&vertices, &previous, &mut f);
+                previous = simplified;
+                error += e;
+            }
+
+            let start = triangles.len();
+            triangles.extend_from_slice(&previous);
+            levels.push(Lod {
+                triangles: start..triangles.len(),
+                error,
+            });
+        }
+
+        LodChain {
+            vertices,
+            triangles,
+            levels,
+        }
+    }
+
+    /// Returns the triangles of level `i`.
+    pub fn level(&self, i: usize) -> &[Triangle<usize>] {
+        &self.triangles[self.levels[i].triangles.clone()]
+    }
+}
@@ -29,12 +29,17 @@ impl Vector3 {
         Vector3::new(self.x / mag, self.y / mag, self.z / mag)
     }
 
+    #[inline]
+    pub fn dot(self, Vector3 { x, y, z }: Vector3) -> f32 {
+        self.x * x + self.y * y + self.z * z
+    }
+
     #[inline]
     pub fn cross(self, Vector3 { x, y, z }: Vector3) -> Vector3 {
         Vector3::new(
-            self.x.mul_add(z, -self.z * y),
-            self.y.mul_add(x, -self.x * z),
-            self.z.mul_add(y, -self.y * x),
+            self.y.mul_add(z, -self.z * y),
+            self.z.mul_add(x, -self.x * z),
+            self.x.mul_add(y, -self.y * x),
         )
     }
 }
@@ -60,6 +65,15 @@ impl From<Vector3> for mint::Vector3<f32> {
     }
 }
 
+impl ops::Add for Vector3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, Vector3 { x, y, z }: Self) -> Self::Output {
+        Vector3::new(self.x + x, self.y + y, self.z + z)
+    }
+}
+
 impl ops::Sub for Vector3 {
     type Output = Self;
 
@@ -69,6 +83,15 @@ impl ops::Sub for Vector3 {
     }
 }
 
+impl ops::Mul<f32> for Vector3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, s: f32) -> Self::Output {
+        Vector3::new(self.x * s, self.y * s, self.z * s)
+    }
+}
+
 impl ops::AddAssign for Vector3 {
     fn add_assign(&mut self, Vector3 { x, y, z }: Self) {
         self.x += x;
@@ -0,0 +1,223 @@
+//! Splits triangle meshes into small clusters suitable for mesh shader
+//! style pipelines and cluster culling.
+
+use crate::math::Vector3;
+use crate::poly::MapVertex;
+use crate::{Normal, Position, Triangle};
+
+/// A small cluster of triangles with a bounded number of vertices and
+/// triangles, along with the bounds needed to cull it as a whole.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Meshlet {
+    /// Indices into the source vertex buffer of every vertex the meshlet uses.
+    pub vertices: Vec<usize>,
+    /// Triangles of the meshlet, indexing into `vertices`.
+    pub triangles: Vec<Triangle<u8>>,
+    /// Center of the bounding sphere.
+    pub center: Position,
+    /// Radius of the bounding sphere.
+    pub radius: f32,
+    /// Axis of the cone that bounds the normals of all the triangles.
+    pub cone_axis: Normal,
+    /// Cutoff of the normal cone. The whole meshlet faces away from a camera
+    /// at `eye` if `dot(normalize(center - eye), cone_axis)` is at least
+    /// `cone_cutoff + radius / length(center - eye)`. This is `1` when the
+    /// normals are spread too wide for the meshlet to ever be culled.
+    pub cone_cutoff: f32,
+}
+
+/// Builds [`Meshlet`]s out of an indexed triangle list.
+///
+/// Triangles are gathered greedily: each meshlet is grown by the triangle
+/// that adds the fewest new vertices to it, until either limit is reached.
+///
+/// [`Meshlet`]: struct.Meshlet.html
+#[derive(Clone, Copy, Debug)]
+pub struct MeshletBuilder {
+    max_vertices: usize,
+    max_triangles: usize,
+}
+
+impl MeshletBuilder {
+    /// Creates a new `MeshletBuilder`.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_vertices` is the vertex limit per meshlet, it must be between 3 and 256
+    /// - `max_triangles` is the triangle limit per meshlet, it must be non-zero
+    ///
+    /// # Panics
+    ///
+    /// This function panics if either limit is out of range.
+    pub fn new(max_vertices: usize, max_triangles: usize) -> Self {
+        assert!((3..=256).contains(&max_vertices) && max_triangles > 0);
+        MeshletBuilder {
+            max_vertices,
+            max_triangles,
+        }
+    }
+
+    /// Splits `triangles` into meshlets. Every triangle ends up in exactly
+    /// one meshlet.
+    ///
+    /// You must supply a function that can be used to lookup
+    /// the position which is needed to calculate the bounds.
+    pub fn build<T, I, F>(&self, vertices: &[T], triangles: I, mut f: F) -> Vec<Meshlet>
+    where
+        I: IntoIterator<Item = Triangle<usize>>,
+        F: FnMut(&T) -> Position,
+    {
+        let triangles: Vec<_> = triangles.into_iter().collect();
+
+        let mut vertex_triangles = vec![Vec::new(); vertices.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            vertex_triangles[t.x].push(i);
+            vertex_triangles[t.y].push(i);
+            vertex_triangles[t.z].push(i);
+        }
+
+        // local index of each vertex in the meshlet being built
+        let mut local: Vec<Option<u8>> = vec![None; vertices.len()];
+        let mut used = vec![false; triangles.len()];
+        let mut meshlets = Vec::new();
+        let mut seed = 0;
+
+        loop {
+            while seed < triangles.len() && used[seed] {
+                seed += 1;
+            }
+            if seed == triangles.len() {
+                break;
+            }
+
+            let mut meshlet_vertices = Vec::new();
+            let mut meshlet_triangles = Vec::new();
+            let mut next = Some(seed);
+
+            while let Some(t) = next {
+                used[t] = true;
+                let tri = triangles[t].map_vertex(|v| {
+                    *local[v].get_or_insert_with(|| {
+                        meshlet_vertices.push(v);
+                        (meshlet_vertices.len() - 1) as u8
+                    })
+                });
+                meshlet_triangles.push(tri);
+
+                if meshlet_triangles.len() == self.max_triangles {
+                    break;
+                }
+
+                // pick the neighbor that adds the fewest new vertices
+                next = None;
+                let mut best_cost = usize::MAX;
+                for &v in &meshlet_vertices {
+                    for &c in &vertex_triangles[v] {
+                        if used[c] {
+                            continue;
+                        }
+                        let Triangle { x, y, z } = triangles[c];
+                        let cost = [x, y, z].iter().filter(|&&v| local[v].is_none()).count();
+                        if meshlet_vertices.len() + cost <= self.max_vertices && cost < best_cost {
+                            next = Some(c);
+                            best_cost = cost;
+                        }
+                    }
+                }
+            }
+
+            for &v in &meshlet_vertices {
+                local[v] = None;
+            }
+
+            let positions: Vec<Vector3> = meshlet_vertices
+                .iter()
+                .map(|&v| Vector3::from(f(&vertices[v])))
+                .collect();
+            meshlets.push(Meshlet::new(
+                meshlet_vertices,
+                meshlet_triangles,
+                &positions,
+            ));
+        }
+
+        meshlets
+    }
+}
+
+impl Meshlet {
+    fn new(vertices: Vec<usize>, triangles: Vec<Triangle<u8>>, positions: &[Vector3]) -> Self {
+        let (center, radius) = bounding_sphere(positions);
+
+        let normals: Vec<Vector3> = triangles
+            .iter()
+            .map(|t| {
+                let a = positions[t.x as usize];
+                let b = positions[t.y as usize];
+                let c = positions[t.z as usize];
+                (b - a).cross(c - a)
+            })
+            .filter(|n| n.magnitude_squared() > 0.)
+            .map(Vector3::normalized)
+            .collect();
+
+        let mut axis = Vector3::new(0., 0., 0.);
+        for &n in &normals {
+            axis += n;
+        }
+        let (cone_axis, cone_cutoff) = if axis.magnitude_squared() > 0. {
+            let axis = axis.normalized();
+            let min_dot = normals.iter().fold(1f32, |m, &n| m.min(n.dot(axis)));
+            if min_dot <= 0. {
+                (axis, 1.)
+            } else {
+                (axis, (1. - min_dot * min_dot).sqrt())
+            }
+        } else {
+            (Vector3::new(0., 0., 1.), 1.)
+        };
+
+        Meshlet {
+            vertices,
+            triangles,
+            center: center.into(),
+            radius,
+            cone_axis: cone_axis.into(),
+            cone_cutoff,
+        }
+    }
+}
+
+// Ritter's bounding sphere, which is within a few percent of optimal.
+fn bounding_sphere(points: &[Vector3]) -> (Vector3, f32) {
+    let farthest = |from: Vector3| {
+        points
+            .iter()
+            .copied()
+            .fold((from, 0.), |(best, d), p| {
+                let dp = (p - from).magnitude_squared();
+                if dp > d {
+                    (p, dp)
+                } else {
+                    (best, d)
+                }
+            })
+            .0
+    };
+
+    let a = farthest(points[0]);
+    let b = farthest(a);
+    let mut center = (a + b) * 0.5;
+    let mut radius = (b - a).magnitude() * 0.5;
+
+    for &p in points {
+        let d = (p - center).magnitude();
+        if d > radius {
+            let new_radius = (radius + d) * 0.5;
+            center = center + (p - center) * ((new_radius - radius) / d);
+            radius = new_radius;
+        }
+    }
+
+    (center, radius)
+}
@@ -24,7 +24,7 @@ impl<T> Neighbors<T> {
         let mut shares_vertex = HashMap::new();
 
         for (i, p) in polygons.iter().enumerate() {
-            p.clone().emit_lines(|line| {
+            p.emit_lines(|line| {
                 shares_vertex.entry(line.x).or_insert_with(Vec::new).push(i);
                 shares_vertex.entry(line.y).or_insert_with(Vec::new).push(i);
                 shares_edge.entry(line).or_insert_with(Vec::new).push(i);
@@ -58,7 +58,7 @@ impl<T> Neighbors<T> {
     pub fn polygon_neighbors(&self, i: usize) -> Option<HashSet<usize>> {
         self.polygons.get(i).map(|x| {
             let mut v = HashSet::new();
-            x.clone().emit_lines(|line| {
+            x.emit_lines(|line| {
                 if let Some(x) = self.shares_edge.get(&line) {
                     for &i in x {
                         v.insert(i);
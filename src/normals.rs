@@ -0,0 +1,301 @@
+//! Normal generation for indexed triangle meshes.
+
+use crate::math::Vector3;
+use crate::poly::MapVertex;
+use crate::topology::{find, union};
+use crate::{Normal, Position, Triangle};
+
+/// How the normals of the faces around a vertex are weighed against each
+/// other when they are averaged into the vertex normal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalWeight {
+    /// Every face counts the same, as with `Neighbors::normal_for_vertex`.
+    Uniform,
+    /// Faces count by their area, so that large faces dominate.
+    Area,
+    /// Faces count by the angle of their corner at the vertex. This keeps
+    /// the normal independent of how the surface is triangulated.
+    Angle,
+}
+
+/// Generates smooth vertex normals, while keeping the edges sharper than a
+/// crease angle hard.
+///
+/// Around every vertex, the faces that meet at an angle below the crease
+/// angle are smoothed together. If that leaves more than one group of
+/// faces, the vertex is split into one copy per group, so hard edges stay
+/// crisp.
+///
+/// Connectivity is taken from the indices alone, so vertices that share a
+/// position but not an index are not smoothed together.
+#[derive(Clone, Copy, Debug)]
+pub struct SmoothNormals {
+    crease_angle: f32,
+    weight: NormalWeight,
+}
+
+impl SmoothNormals {
+    /// Creates a new `SmoothNormals` with the crease angle given in
+    /// radians, and angle weighting. A crease angle of `PI` or more smooths
+    /// everything together.
+    pub fn new(crease_angle: f32) -> Self {
+        SmoothNormals {
+            crease_angle,
+            weight: NormalWeight::Angle,
+        }
+    }
+
+    /// Sets how the face normals are weighed.
+    pub fn weight(self, weight: NormalWeight) -> Self {
+        SmoothNormals { weight, ..self }
+    }
+
+    /// Generates the normals, returning the new vertex buffer along with
+    /// the triangles remapped onto it. Vertices that no triangle uses are
+    /// dropped.
+    ///
+    /// `position` looks up the position of a vertex, and `with_normal`
+    /// builds an output vertex from a source vertex and its new normal.
+    pub fn generate<T, U, F, G>(
+        &self,
+        vertices: &[T],
+        triangles: &[Triangle<usize>],
+        mut position: F,
+        mut with_normal: G,
+    ) -> (Vec<U>, Vec<Triangle<usize>>)
+    where
+        F: FnMut(&T) -> Position,
+        G: FnMut(&T, Normal) -> U,
+    {
+        let positions: Vec<Vector3> = vertices
+            .iter()
+            .map(|v| Vector3::from(position(v)))
+            .collect();
+
+        let face_normals: Vec<Vector3> = triangles
+            .iter()
+            .map(|t| face_normal(&positions, t).into())
+            .collect();
+
+        // the corners of the triangles around every vertex
+        let mut corners = vec![Vec::new(); vertices.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            corners[t.x].push((i, 0));
+            corners[t.y].push((i, 1));
+            corners[t.z].push((i, 2));
+        }
+
+        let cos_crease = self.crease_angle.cos();
+        let mut out = Vec::with_capacity(vertices.len());
+        let mut remapped: Vec<[usize; 3]> = triangles.iter().map(|t| [t.x, t.y, t.z]).collect();
+
+        for (v, around) in corners.iter().enumerate() {
+            // group the faces that share an edge at this vertex, and meet
+            // at less than the crease angle
+            let mut group: Vec<usize> = (0..around.len()).collect();
+            for a in 0..around.len() {
+                for b in a + 1..around.len() {
+                    let (ta, tb) = (around[a].0, around[b].0);
+                    let Triangle { x, y, z } = triangles[ta];
+                    let share_edge = [x, y, z]
+                        .iter()
+                        .any(|&u| u != v && contains(&triangles[tb], u));
+                    if share_edge && face_normals[ta].dot(face_normals[tb]) >= cos_crease {
+                        union(&mut group, a, b);
+                    }
+                }
+            }
+
+            let mut group_vertex: Vec<Option<usize>> = vec![None; around.len()];
+            for c in 0..around.len() {
+                let root = find(&mut group, c);
+                let index = match group_vertex[root] {
+                    Some(index) => index,
+                    None => {
+                        let mut normal = Vector3::new(0., 0., 0.);
+                        for (d, &(t, corner)) in around.iter().enumerate() {
+                            if find(&mut group, d) == root {
+                                let w = self.corner_weight(&positions, &triangles[t], corner);
+                                normal += face_normals[t] * w;
+                            }
+                        }
+                        if normal.magnitude_squared() > 0. {
+                            normal = normal.normalized();
+                        }
+                        out.push(with_normal(&vertices[v], normal.into()));
+                        group_vertex[root] = Some(out.len() - 1);
+                        out.len() - 1
+                    }
+                };
+                let (t, corner) = around[c];
+                remapped[t][corner] = index;
+            }
+        }
+
+        let triangles = remapped
+            .into_iter()
+            .map(|[x, y, z]| Triangle::new(x, y, z))
+            .collect();
+        (out, triangles)
+    }
+
+    fn corner_weight(&self, positions: &[Vector3], t: &Triangle<usize>, corner: usize) -> f32 {
+        let p = [positions[t.x], positions[t.y], positions[t.z]];
+        match self.weight {
+            NormalWeight::Uniform => 1.,
+            NormalWeight::Area => (p[1] - p[0]).cross(p[2] - p[0]).magnitude(),
+            NormalWeight::Angle => {
+                let a = p[(corner + 1) % 3] - p[corner];
+                let b = p[(corner + 2) % 3] - p[corner];
+                let d = (a.magnitude() * b.magnitude()).max(f32::MIN_POSITIVE);
+                (a.dot(b) / d).clamp(-1., 1.).acos()
+            }
+        }
+    }
+}
+
+/// Which corner of a triangle the GPU takes flat attributes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProvokingVertex {
+    /// The first corner, the convention of Vulkan and Direct3D.
+    First,
+    /// The last corner, the default convention of OpenGL.
+    Last,
+}
+
+fn face_normal(positions: &[Vector3], t: &Triangle<usize>) -> Normal {
+    let n = (positions[t.y] - positions[t.x]).cross(positions[t.z] - positions[t.x]);
+    if n.magnitude_squared() > 0. {
+        n.normalized().into()
+    } else {
+        n.into()
+    }
+}
+
+/// Expands an indexed triangle mesh for flat shading, giving every
+/// triangle its own copy of its vertices, all carrying the face normal.
+///
+/// `position` looks up the position of a vertex, and `with_normal`
+/// builds an output vertex from a source vertex and the face normal.
+pub fn flat_shade<T, U, F, G>(
+    vertices: &[T],
+    triangles: &[Triangle<usize>],
+    mut position: F,
+    mut with_normal: G,
+) -> Vec<Triangle<U>>
+where
+    F: FnMut(&T) -> Position,
+    G: FnMut(&T, Normal) -> U,
+{
+    let positions: Vec<Vector3> = vertices
+        .iter()
+        .map(|v| Vector3::from(position(v)))
+        .collect();
+
+    triangles
+        .iter()
+        .map(|t| {
+            let n = face_normal(&positions, t);
+            t.map_vertex(|i| with_normal(&vertices[i], n))
+        })
+        .collect()
+}
+
+/// Prepares an indexed triangle mesh for flat shading with flat
+/// interpolated normals, while keeping it indexed.
+///
+/// Each triangle is rotated so that its provoking vertex, the one the GPU
+/// takes flat attributes from, is a vertex that no other triangle uses as
+/// its provoking vertex, and that vertex is given the face normal. Vertices
+/// are only duplicated when every corner of a triangle is already taken,
+/// so the output has about as many vertices as triangles, rather than three
+/// times as many. The winding of the triangles is kept.
+///
+/// `position` looks up the position of a vertex, and `with_normal`
+/// builds an output vertex from a source vertex and a normal.
+pub fn flat_shade_indexed<T, U, F, G>(
+    vertices: &[T],
+    triangles: &[Triangle<usize>],
+    provoking: ProvokingVertex,
+    mut position: F,
+    mut with_normal: G,
+) -> (Vec<U>, Vec<Triangle<usize>>)
+where
+    F: FnMut(&T) -> Position,
+    G: FnMut(&T, Normal) -> U,
+{
+    let positions: Vec<Vector3> = vertices
+        .iter()
+        .map(|v| Vector3::from(position(v)))
+        .collect();
+
+    let mut out = Vec::with_capacity(vertices.len());
+    // the first output copy of every source vertex, and whether it is
+    // taken as the provoking vertex of a triangle already
+    let mut copy: Vec<Option<(usize, bool)>> = vec![None; vertices.len()];
+    let mut result = Vec::with_capacity(triangles.len());
+
+    for t in triangles {
+        let n = face_normal(&positions, t);
+        let corners = [t.x, t.y, t.z];
+
+        let free = corners
+            .iter()
+            .position(|&v| !copy[v].is_some_and(|(_, taken)| taken));
+        let (corner, index) = match free {
+            Some(corner) => {
+                let v = corners[corner];
+                let vertex = with_normal(&vertices[v], n);
+                let index = match copy[v] {
+                    Some((index, _)) => {
+                        out[index] = vertex;
+                        index
+                    }
+                    None => {
+                        out.push(vertex);
+                        out.len() - 1
+                    }
+                };
+                copy[v] = Some((index, true));
+                (corner, index)
+            }
+            None => {
+                out.push(with_normal(&vertices[corners[0]], n));
+                (0, out.len() - 1)
+            }
+        };
+
+        let mut mapped = [0; 3];
+        for (i, &v) in corners.iter().enumerate() {
+            mapped[i] = if i == corner {
+                index
+            } else {
+                match copy[v] {
+                    Some((index, _)) => index,
+                    None => {
+                        out.push(with_normal(&vertices[v], n));
+                        copy[v] = Some((out.len() - 1, false));
+                        out.len() - 1
+                    }
+                }
+            };
+        }
+
+        // rotate the provoking corner into place, keeping the winding
+        let first = match provoking {
+            ProvokingVertex::First => corner,
+            ProvokingVertex::Last => (corner + 1) % 3,
+        };
+        result.push(Triangle::new(
+            mapped[first],
+            mapped[(first + 1) % 3],
+            mapped[(first + 2) % 3],
+        ));
+    }
+
+    (out, result)
+}
+
+fn contains(t: &Triangle<usize>, v: usize) -> bool {
+    t.x == v || t.y == v || t.z == v
+}
@@ -0,0 +1,373 @@
+//! Reading and writing Wavefront OBJ files.
+//!
+//! ```
+//! use genmesh::generators::{Cube, IcoSphere};
+//! use genmesh::obj::ObjWriter;
+//!
+//! let mut writer = ObjWriter::new(Vec::new());
+//! writer.write_generator("cube", &Cube::new()).unwrap();
+//! writer.write_generator("sphere", &IcoSphere::new()).unwrap();
+//!
+//! let obj = String::from_utf8(writer.into_inner()).unwrap();
+//! assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 6 + 20);
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+
+use crate::generators::{IndexedPolygon, SharedVertex};
+use crate::{
+    EmitVertices, Normal, Polygon, Position, Quad, ReadError, TexCoord, Triangle, VertexAttributes,
+};
+
+/// Writes meshes to an OBJ file.
+///
+/// Positions, texture coordinates and normals are written as `v`, `vt`
+/// and `vn` lines, followed by the faces that use them. Triangles and
+/// quads are kept as they are. Several meshes can be written one after
+/// another, each in its own named group.
+#[derive(Debug)]
+pub struct ObjWriter<W> {
+    out: W,
+    positions: usize,
+    tex_coords: usize,
+    normals: usize,
+}
+
+// the 1-based indices of the attributes of a written vertex
+#[derive(Clone, Copy)]
+struct Corner {
+    position: usize,
+    tex_coord: Option<usize>,
+    normal: Option<usize>,
+}
+
+impl<W: Write> ObjWriter<W> {
+    /// Creates a new `ObjWriter` writing into `out`.
+    pub fn new(out: W) -> Self {
+        ObjWriter {
+            out,
+            positions: 0,
+            tex_coords: 0,
+            normals: 0,
+        }
+    }
+
+    /// Starts a new group, which the faces written after it belong to.
+    pub fn group(&mut self, name: &str) -> io::Result<()> {
+        writeln!(self.out, "g {}", name)
+    }
+
+    /// Sets the material of the faces written after it.
+    pub fn material(&mut self, name: &str) -> io::Result<()> {
+        writeln!(self.out, "usemtl {}", name)
+    }
+
+    /// Writes a stream of polygons, such as the output of a generator.
+    /// Every polygon gets its own copy of its vertices.
+    pub fn write_polygons<I, P, V>(&mut self, polygons: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = P>,
+        P: EmitVertices<V>,
+        V: VertexAttributes,
+    {
+        let mut vertices = Vec::with_capacity(4);
+        for p in polygons {
+            vertices.clear();
+            p.emit_vertices(|v| vertices.push(v));
+            let mut corners = Vec::with_capacity(vertices.len());
+            for v in &vertices {
+                corners.push(self.write_vertex(v)?);
+            }
+            self.write_face(&corners)?;
+        }
+        Ok(())
+    }
+
+    /// Writes an indexed mesh, with polygons indexing into `vertices`.
+    pub fn write_indexed<V, P>(&mut self, vertices: &[V], polygons: &[P]) -> io::Result<()>
+    where
+        V: VertexAttributes,
+        P: EmitVertices<usize> + Clone,
+    {
+        let corners = vertices
+            .iter()
+            .map(|v| self.write_vertex(v))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut face = Vec::with_capacity(4);
+        for p in polygons {
+            face.clear();
+            p.clone().emit_vertices(|i| face.push(corners[i]));
+            self.write_face(&face)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the indexed mesh of a generator, in a group called `name`.
+    pub fn write_generator<G, V, P>(&mut self, name: &str, generator: &G) -> io::Result<()>
+    where
+        G: SharedVertex<V> + IndexedPolygon<P>,
+        V: VertexAttributes,
+        P: EmitVertices<usize> + Clone,
+    {
+        let vertices: Vec<V> = generator.shared_vertex_iter().collect();
+        let polygons: Vec<P> = generator.indexed_polygon_iter().collect();
+        self.group(name)?;
+        self.write_indexed(&vertices, &polygons)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn write_vertex<V: VertexAttributes>(&mut self, v: &V) -> io::Result<Corner> {
+        let p = v.position();
+        writeln!(self.out, "v {} {} {}", p.x, p.y, p.z)?;
+        self.positions += 1;
+        let tex_coord = match v.tex_coord() {
+            Some(t) => {
+                writeln!(self.out, "vt {} {}", t.x, t.y)?;
+                self.tex_coords += 1;
+                Some(self.tex_coords)
+            }
+            None => None,
+        };
+        let normal = match v.normal() {
+            Some(n) => {
+                writeln!(self.out, "vn {} {} {}", n.x, n.y, n.z)?;
+                self.normals += 1;
+                Some(self.normals)
+            }
+            None => None,
+        };
+        Ok(Corner {
+            position: self.positions,
+            tex_coord,
+            normal,
+        })
+    }
+
+    fn write_face(&mut self, corners: &[Corner]) -> io::Result<()> {
+        write!(self.out, "f")?;
+        for c in corners {
+            match (c.tex_coord, c.normal) {
+                (None, None) => write!(self.out, " {}", c.position)?,
+                (Some(t), None) => write!(self.out, " {}/{}", c.position, t)?,
+                (None, Some(n)) => write!(self.out, " {}//{}", c.position, n)?,
+                (Some(t), Some(n)) => write!(self.out, " {}/{}/{}", c.position, t, n)?,
+            }
+        }
+        writeln!(self.out)
+    }
+}
+
+/// A vertex read from an OBJ file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjVertex {
+    /// The position of the vertex.
+    pub position: Position,
+    /// The texture coordinate of the vertex, if the faces gave one.
+    pub tex_coord: Option<TexCoord>,
+    /// The normal of the vertex, if the faces gave one.
+    pub normal: Option<Normal>,
+}
+
+impl VertexAttributes for ObjVertex {
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn normal(&self) -> Option<Normal> {
+        self.normal
+    }
+
+    fn tex_coord(&self) -> Option<TexCoord> {
+        self.tex_coord
+    }
+}
+
+/// A named range of the polygons of an [`ObjMesh`].
+///
+/// [`ObjMesh`]: struct.ObjMesh.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaceRange {
+    /// The name of the group or material.
+    pub name: String,
+    /// The range of `ObjMesh::polygons` it covers.
+    pub polygons: Range<usize>,
+}
+
+/// An indexed mesh read from an OBJ file.
+///
+/// OBJ faces index positions, texture coordinates and normals separately.
+/// Every distinct combination of the three becomes one of `vertices`, which
+/// `polygons` index into, while `position_polygons` index the same faces
+/// into `positions` alone. Use the latter for topology, since vertices that
+/// only differ in their normals or texture coordinates are split in the
+/// former.
+///
+/// Faces with more than four vertices are split into a fan of triangles.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObjMesh {
+    /// The distinct vertices used by the faces.
+    pub vertices: Vec<ObjVertex>,
+    /// The faces, indexing into `vertices`.
+    pub polygons: Vec<Polygon<usize>>,
+    /// All the positions of the file.
+    pub positions: Vec<Position>,
+    /// The faces, indexing into `positions`.
+    pub position_polygons: Vec<Polygon<usize>>,
+    /// The ranges of faces that follow each `g` or `o` statement.
+    pub groups: Vec<FaceRange>,
+    /// The ranges of faces that follow each `usemtl` statement.
+    pub materials: Vec<FaceRange>,
+}
+
+/// Reads an OBJ file into an indexed mesh.
+///
+/// Statements other than vertex data, faces, groups and materials are
+/// ignored.
+///
+/// ```
+/// use genmesh::obj;
+///
+/// let mesh = obj::read("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n".as_bytes()).unwrap();
+/// assert_eq!(mesh.vertices.len(), 3);
+/// assert_eq!(mesh.polygons.len(), 1);
+/// ```
+pub fn read<R: BufRead>(input: R) -> Result<ObjMesh, ReadError> {
+    let mut mesh = ObjMesh::default();
+    let mut tex_coords: Vec<TexCoord> = Vec::new();
+    let mut normals: Vec<Normal> = Vec::new();
+    let mut vertex_of: HashMap<(usize, Option<usize>, Option<usize>), usize> = HashMap::new();
+
+    for (number, line) in input.lines().enumerate() {
+        let number = number + 1;
+        let line = line?;
+        let line = line.split('#').next().unwrap().trim();
+        let mut words = line.split_whitespace();
+        let keyword = match words.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let floats = |count: usize, required: usize| -> Result<Vec<f32>, ReadError> {
+            let values = line
+                .split_whitespace()
+                .skip(1)
+                .take(count)
+                .map(|w| w.parse::<f32>())
+                .collect::<Result<Vec<f32>, _>>()
+                .map_err(|e| ReadError::parse(number, format!("{}: {}", keyword, e)))?;
+            if values.len() < required {
+                return Err(ReadError::parse(
+                    number,
+                    format!("{} needs {} values", keyword, required),
+                ));
+            }
+            Ok(values)
+        };
+
+        match keyword {
+            "v" => {
+                let v = floats(3, 3)?;
+                mesh.positions.push([v[0], v[1], v[2]].into());
+            }
+            "vt" => {
+                let v = floats(2, 1)?;
+                tex_coords.push([v[0], v.get(1).copied().unwrap_or(0.)].into());
+            }
+            "vn" => {
+                let v = floats(3, 3)?;
+                normals.push([v[0], v[1], v[2]].into());
+            }
+            "f" => {
+                let mut face = Vec::new();
+                let mut position_face = Vec::new();
+                for corner in words {
+                    let mut parts = corner.split('/');
+                    let resolve = |part: Option<&str>, len: usize, what: &str| match part {
+                        None | Some("") => Ok(None),
+                        Some(part) => index(part, len).map(Some).ok_or_else(|| {
+                            ReadError::parse(number, format!("bad {} index {}", what, part))
+                        }),
+                    };
+                    let position = resolve(parts.next(), mesh.positions.len(), "position")?
+                        .ok_or_else(|| ReadError::parse(number, "missing position index"))?;
+                    let tex_coord = resolve(parts.next(), tex_coords.len(), "texture")?;
+                    let normal = resolve(parts.next(), normals.len(), "normal")?;
+
+                    let key = (position, tex_coord, normal);
+                    let (vertices, positions) = (&mut mesh.vertices, &mesh.positions);
+                    let v = *vertex_of.entry(key).or_insert_with(|| {
+                        vertices.push(ObjVertex {
+                            position: positions[position],
+                            tex_coord: tex_coord.map(|t| tex_coords[t]),
+                            normal: normal.map(|n| normals[n]),
+                        });
+                        vertices.len() - 1
+                    });
+                    face.push(v);
+                    position_face.push(position);
+                }
+                if face.len() < 3 {
+                    return Err(ReadError::parse(number, "face has fewer than 3 vertices"));
+                }
+                emit_face(&face, &mut mesh.polygons);
+                emit_face(&position_face, &mut mesh.position_polygons);
+            }
+            "g" | "o" => start_range(&mut mesh.groups, words, mesh.polygons.len()),
+            "usemtl" => start_range(&mut mesh.materials, words, mesh.polygons.len()),
+            _ => {}
+        }
+    }
+
+    let end = mesh.polygons.len();
+    for ranges in [&mut mesh.groups, &mut mesh.materials].iter_mut() {
+        if let Some(last) = ranges.last_mut() {
+            last.polygons.end = end;
+        }
+        ranges.retain(|r| !r.polygons.is_empty());
+    }
+    Ok(mesh)
+}
+
+// resolves a 1-based or negative relative OBJ index
+fn index(word: &str, len: usize) -> Option<usize> {
+    let i: isize = word.parse().ok()?;
+    let i = if i < 0 { len as isize + i } else { i - 1 };
+    if i >= 0 && (i as usize) < len {
+        Some(i as usize)
+    } else {
+        None
+    }
+}
+
+// pushes a face as it is, or as a fan of triangles when it has more than four vertices
+pub(crate) fn emit_face(face: &[usize], polygons: &mut Vec<Polygon<usize>>) {
+    match *face {
+        [x, y, z] => polygons.push(Polygon::PolyTri(Triangle::new(x, y, z))),
+        [x, y, z, w] => polygons.push(Polygon::PolyQuad(Quad::new(x, y, z, w))),
+        _ => {
+            for i in 1..face.len() - 1 {
+                polygons.push(Polygon::PolyTri(Triangle::new(
+                    face[0],
+                    face[i],
+                    face[i + 1],
+                )));
+            }
+        }
+    }
+}
+
+fn start_range<'a, I: Iterator<Item = &'a str>>(ranges: &mut Vec<FaceRange>, name: I, at: usize) {
+    if let Some(last) = ranges.last_mut() {
+        last.polygons.end = at;
+    }
+    let name: Vec<&str> = name.collect();
+    ranges.push(FaceRange {
+        name: name.join(" "),
+        polygons: at..at,
+    });
+}
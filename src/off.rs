@@ -0,0 +1,276 @@
+//! Reading and writing OFF files, including the colored COFF variant.
+//!
+//! An OFF file lists positions followed by faces of any size, each face
+//! optionally followed by its color. A COFF file also gives every vertex a
+//! color. Faces with more than four vertices are split into a fan of
+//! triangles when reading, and the color of the face is given to each of
+//! them.
+//!
+//! ```
+//! use genmesh::generators::{Cube, IndexedPolygon, SharedVertex};
+//! use genmesh::off;
+//!
+//! let cube = Cube::new();
+//! let vertices: Vec<_> = cube.shared_vertex_iter().collect();
+//! let quads: Vec<_> = cube.indexed_polygon_iter().collect();
+//! let colors = vec![[1., 0., 0., 1.]; quads.len()];
+//!
+//! let mut data = Vec::new();
+//! off::write_colored(&mut data, &vertices, &[], &quads, &colors).unwrap();
+//! let mesh = off::read(&data[..]).unwrap();
+//! assert_eq!(mesh.polygons.len(), 6);
+//! assert_eq!(mesh.face_colors, colors);
+//! ```
+
+use std::io::{self, BufRead, Write};
+
+use crate::obj::emit_face;
+use crate::{EmitVertices, Polygon, Position, ReadError, VertexAttributes};
+
+/// An RGBA color, with components from 0 to 1.
+pub type Color = [f32; 4];
+
+/// An indexed mesh read from an OFF file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OffMesh {
+    /// The positions of the vertices.
+    pub positions: Vec<Position>,
+    /// The colors of the vertices of a COFF file, or nothing.
+    pub vertex_colors: Vec<Color>,
+    /// The faces, indexing into `positions`.
+    pub polygons: Vec<Polygon<usize>>,
+    /// The color of every polygon, or nothing when the faces of the file
+    /// have no colors.
+    pub face_colors: Vec<Color>,
+}
+
+/// Writes an indexed mesh to an OFF file.
+pub fn write<W, V, P>(out: W, vertices: &[V], polygons: &[P]) -> io::Result<()>
+where
+    W: Write,
+    V: VertexAttributes,
+    P: EmitVertices<usize> + Clone,
+{
+    write_colored(out, vertices, &[], polygons, &[])
+}
+
+/// Writes an indexed mesh to an OFF file along with the colors of its
+/// vertices, making it a COFF file, and of its faces.
+///
+/// Either list of colors can be empty to leave those colors out, and must
+/// have one color per vertex or polygon otherwise.
+pub fn write_colored<W, V, P>(
+    mut out: W,
+    vertices: &[V],
+    vertex_colors: &[Color],
+    polygons: &[P],
+    face_colors: &[Color],
+) -> io::Result<()>
+where
+    W: Write,
+    V: VertexAttributes,
+    P: EmitVertices<usize> + Clone,
+{
+    if !vertex_colors.is_empty() && vertex_colors.len() != vertices.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "vertex colors do not match the vertices",
+        ));
+    }
+    if !face_colors.is_empty() && face_colors.len() != polygons.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "face colors do not match the polygons",
+        ));
+    }
+
+    let keyword = if vertex_colors.is_empty() {
+        "OFF"
+    } else {
+        "COFF"
+    };
+    writeln!(out, "{}", keyword)?;
+    writeln!(out, "# written by genmesh")?;
+    // the edge count is unused by readers, so it is left at zero
+    writeln!(out, "{} {} 0", vertices.len(), polygons.len())?;
+
+    for (i, v) in vertices.iter().enumerate() {
+        let p = v.position();
+        write!(out, "{} {} {}", p.x, p.y, p.z)?;
+        if let Some(c) = vertex_colors.get(i) {
+            write_color(&mut out, c)?;
+        }
+        writeln!(out)?;
+    }
+
+    let mut face = Vec::with_capacity(4);
+    for (i, p) in polygons.iter().enumerate() {
+        face.clear();
+        p.clone().emit_vertices(|v| face.push(v));
+        write!(out, "{}", face.len())?;
+        for v in &face {
+            write!(out, " {}", v)?;
+        }
+        if let Some(c) = face_colors.get(i) {
+            write_color(&mut out, c)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+// colors are written with a decimal point, since integer components are
+// read as being from 0 to 255
+fn write_color<W: Write>(out: &mut W, c: &Color) -> io::Result<()> {
+    write!(out, " {:?} {:?} {:?} {:?}", c[0], c[1], c[2], c[3])
+}
+
+/// Reads an OFF or COFF file into an indexed mesh.
+///
+/// Colors may be given as three or four components, either as floats from
+/// 0 to 1 or as integers from 0 to 255. Either all the faces or none of them
+/// must have a color.
+///
+/// ```
+/// use genmesh::off;
+///
+/// let data = "OFF 4 1 0\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n4 0 1 2 3 255 0 0\n";
+/// let mesh = off::read(data.as_bytes()).unwrap();
+/// assert_eq!(mesh.polygons.len(), 1);
+/// assert_eq!(mesh.face_colors, vec![[1., 0., 0., 1.]]);
+/// ```
+pub fn read<R: BufRead>(input: R) -> Result<OffMesh, ReadError> {
+    let mut mesh = OffMesh::default();
+    let mut lines = input.lines().enumerate().filter_map(|(number, line)| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(ReadError::Io(e))),
+        };
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            None
+        } else {
+            Some(Ok((number + 1, line.to_owned())))
+        }
+    });
+    let mut last = 0;
+    let mut next_line = |what: &str| match lines.next() {
+        Some(Ok((number, line))) => {
+            last = number;
+            Ok((number, line))
+        }
+        Some(Err(e)) => Err(e),
+        None => Err(ReadError::parse(last, format!("file ends before {}", what))),
+    };
+
+    // the counts can follow the keyword on the same line
+    let (number, line) = next_line("the header")?;
+    let mut words: Vec<&str> = line.split_whitespace().collect();
+    let colored = match words.remove(0) {
+        "OFF" => false,
+        "COFF" => true,
+        keyword => {
+            return Err(ReadError::parse(
+                number,
+                format!("unsupported format {}", keyword),
+            ))
+        }
+    };
+    let (number, counts) = if words.is_empty() {
+        next_line("the counts")?
+    } else {
+        (number, words.join(" "))
+    };
+    let counts = counts
+        .split_whitespace()
+        .map(|w| w.parse::<usize>())
+        .collect::<Result<Vec<usize>, _>>()
+        .map_err(|e| ReadError::parse(number, format!("bad counts: {}", e)))?;
+    let (vertex_count, face_count) = match counts[..] {
+        [v, f] | [v, f, _] => (v, f),
+        _ => return Err(ReadError::parse(number, "expected vertex and face counts")),
+    };
+
+    for _ in 0..vertex_count {
+        let (number, line) = next_line("all the vertices")?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let expected = if colored { 6 } else { 3 };
+        if words.len() < expected {
+            return Err(ReadError::parse(
+                number,
+                format!("vertex needs at least {} values", expected),
+            ));
+        }
+        let position = floats(&words[..3], number)?;
+        mesh.positions
+            .push([position[0], position[1], position[2]].into());
+        if colored {
+            mesh.vertex_colors.push(color(&words[3..], number)?);
+        }
+    }
+
+    let mut face = Vec::new();
+    for f in 0..face_count {
+        let (number, line) = next_line("all the faces")?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let size = words[0]
+            .parse::<usize>()
+            .map_err(|e| ReadError::parse(number, format!("bad face size: {}", e)))?;
+        if size < 3 {
+            return Err(ReadError::parse(number, "face has fewer than 3 vertices"));
+        }
+        if words.len() < size + 1 {
+            return Err(ReadError::parse(number, "face is missing indices"));
+        }
+
+        face.clear();
+        for w in &words[1..=size] {
+            match w.parse::<usize>() {
+                Ok(i) if i < mesh.positions.len() => face.push(i),
+                _ => return Err(ReadError::parse(number, format!("bad vertex index {}", w))),
+            }
+        }
+        emit_face(&face, &mut mesh.polygons);
+
+        let rest = &words[size + 1..];
+        if f > 0 && rest.is_empty() != mesh.face_colors.is_empty() {
+            return Err(ReadError::parse(
+                number,
+                "either all faces or none must have a color",
+            ));
+        }
+        if !rest.is_empty() {
+            let c = color(rest, number)?;
+            mesh.face_colors.resize(mesh.polygons.len(), c);
+        }
+    }
+    Ok(mesh)
+}
+
+fn floats(words: &[&str], number: usize) -> Result<Vec<f32>, ReadError> {
+    words
+        .iter()
+        .map(|w| w.parse::<f32>())
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(|e| ReadError::parse(number, e.to_string()))
+}
+
+fn color(words: &[&str], number: usize) -> Result<Color, ReadError> {
+    if words.len() != 3 && words.len() != 4 {
+        return Err(ReadError::parse(number, "expected 3 or 4 color components"));
+    }
+    // the scale is picked for the whole color, so `0.5 1 0` stays in 0 to 1
+    let integers = words.iter().all(|w| w.parse::<u32>().is_ok());
+    let mut c = [1.; 4];
+    for (i, w) in words.iter().enumerate() {
+        c[i] = if integers {
+            w.parse::<u8>()
+                .map(|value| value as f32 / 255.)
+                .map_err(|e| ReadError::parse(number, format!("bad color: {}", e)))?
+        } else {
+            w.parse::<f32>()
+                .map_err(|e| ReadError::parse(number, format!("bad color: {}", e)))?
+        };
+    }
+    Ok(c)
+}
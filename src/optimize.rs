@@ -0,0 +1,334 @@
+//! Passes that reorder indexed meshes so that the GPU can consume
+//! them more efficiently.
+
+use crate::math::Vector3;
+use crate::poly::MapVertex;
+use crate::{Position, Triangle};
+
+// Size of the FIFO cache that the cluster passes simulate, this matches
+// the post-transform cache of most hardware closely enough.
+const FIFO_CACHE_SIZE: usize = 16;
+
+// Tuning values for the vertex cache optimizer, these are the values
+// suggested in Tom Forsyth's "Linear-Speed Vertex Cache Optimisation".
+const LRU_CACHE_SIZE: usize = 32;
+const CACHE_DECAY_POWER: f32 = 1.5;
+const LAST_TRIANGLE_SCORE: f32 = 0.75;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+/// Reorders the vertex buffer so that vertices are stored in the order in
+/// which the polygons first reference them, and rewrites the polygons to
+/// point at the new locations. This turns the scattered vertex order left
+/// behind by an [`LruIndexer`] or a triangle reordering pass into a mostly
+/// linear memory access pattern.
+///
+/// Returns the reordered vertices and the remap table. Entry `i` of the
+/// table holds the index of the source vertex that was moved to `i`, so any
+/// other attribute stream can be reordered consistently with
+/// `remap.iter().map(|&i| stream[i].clone())`. Vertices that are not
+/// referenced by any polygon are dropped.
+///
+/// [`LruIndexer`]: struct.LruIndexer.html
+pub fn optimize_vertex_fetch<T, P>(vertices: &[T], polygons: &mut [P]) -> (Vec<T>, Vec<usize>)
+where
+    T: Clone,
+    P: Clone + MapVertex<usize, usize, Output = P>,
+{
+    let mut new_index = vec![None; vertices.len()];
+    let mut remap = Vec::with_capacity(vertices.len());
+
+    for poly in polygons.iter_mut() {
+        *poly = poly.clone().map_vertex(|i| {
+            *new_index[i].get_or_insert_with(|| {
+                remap.push(i);
+                remap.len() - 1
+            })
+        });
+    }
+
+    let vertices = remap.iter().map(|&i| vertices[i].clone()).collect();
+    (vertices, remap)
+}
+
+/// Reorders `triangles` to make better use of the post-transform vertex
+/// cache, using Tom Forsyth's linear-speed vertex cache optimisation. The
+/// triangles themselves and their winding are left untouched.
+///
+/// `vertex_count` is the number of vertices that `triangles` index into.
+pub fn optimize_vertex_cache(triangles: &mut [Triangle<usize>], vertex_count: usize) {
+    let mut vertex_triangles = vec![Vec::new(); vertex_count];
+    for (i, t) in triangles.iter().enumerate() {
+        vertex_triangles[t.x].push(i);
+        vertex_triangles[t.y].push(i);
+        vertex_triangles[t.z].push(i);
+    }
+
+    let mut vertex_score: Vec<f32> = vertex_triangles
+        .iter()
+        .map(|t| vertex_cache_score(None, t.len()))
+        .collect();
+    let mut emitted = vec![false; triangles.len()];
+
+    let mut order = Vec::with_capacity(triangles.len());
+    let mut cache: Vec<usize> = Vec::with_capacity(LRU_CACHE_SIZE + 3);
+    let mut best = None;
+    let mut cursor = 0;
+
+    while order.len() < triangles.len() {
+        // fall back to the input order when the cache runs dry
+        let next = match best {
+            Some(t) => t,
+            None => {
+                while emitted[cursor] {
+                    cursor += 1;
+                }
+                cursor
+            }
+        };
+
+        emitted[next] = true;
+        order.push(next);
+
+        let Triangle { x, y, z } = triangles[next];
+        for &v in &[x, y, z] {
+            vertex_triangles[v].retain(|&t| t != next);
+            if let Some(p) = cache.iter().position(|&c| c == v) {
+                cache.remove(p);
+            }
+            cache.insert(0, v);
+        }
+
+        // everything that got pushed out of the cache, or moved in it,
+        // needs to have its score recalculated
+        for (p, &v) in cache.iter().enumerate() {
+            let position = if p < LRU_CACHE_SIZE { Some(p) } else { None };
+            vertex_score[v] = vertex_cache_score(position, vertex_triangles[v].len());
+        }
+        cache.truncate(LRU_CACHE_SIZE);
+
+        best = None;
+        let mut best_score = -1.0;
+        for &v in &cache {
+            for &t in &vertex_triangles[v] {
+                let Triangle { x, y, z } = triangles[t];
+                let score = vertex_score[x] + vertex_score[y] + vertex_score[z];
+                if score > best_score {
+                    best = Some(t);
+                    best_score = score;
+                }
+            }
+        }
+    }
+
+    let reordered: Vec<_> = order.iter().map(|&i| triangles[i]).collect();
+    triangles.copy_from_slice(&reordered);
+}
+
+fn vertex_cache_score(cache_position: Option<usize>, remaining: usize) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+
+    let cache_score = match cache_position {
+        None => 0.0,
+        Some(p) if p < 3 => LAST_TRIANGLE_SCORE,
+        Some(p) => {
+            let scale = 1.0 / (LRU_CACHE_SIZE - 3) as f32;
+            (1.0 - (p - 3) as f32 * scale).powf(CACHE_DECAY_POWER)
+        }
+    };
+
+    cache_score + VALENCE_BOOST_SCALE * (remaining as f32).powf(-VALENCE_BOOST_POWER)
+}
+
+/// Calculates the average cache miss ratio (ACMR) of `triangles`, which is
+/// the number of vertex transformations per triangle when rendered through
+/// a FIFO post-transform cache holding `cache_size` vertices. The result
+/// lies between roughly 0.5 for an ideal ordering and 3.0 for the worst.
+pub fn average_cache_miss_ratio(
+    triangles: &[Triangle<usize>],
+    vertex_count: usize,
+    cache_size: usize,
+) -> f32 {
+    if triangles.is_empty() {
+        return 0.0;
+    }
+
+    let mut cache = FifoCache::new(vertex_count, cache_size);
+    let misses: usize = triangles.iter().map(|t| cache.insert(t)).sum();
+    misses as f32 / triangles.len() as f32
+}
+
+/// Reorders the triangles of a cache-optimized mesh to reduce overdraw.
+///
+/// The triangles are split into clusters along the boundaries of the
+/// existing cache-friendly order, and the clusters are sorted so that the
+/// ones that face away from the center of the mesh, and thus are most
+/// likely to occlude the rest, are drawn first. The triangles are expected
+/// to be run through [`optimize_vertex_cache`] beforehand.
+///
+/// `threshold` bounds how much vertex cache efficiency may be sacrificed:
+/// the clusters are cut down as long as their average cache miss ratio
+/// stays within `threshold` times that of the input order, so `1.0` keeps
+/// the input clusters whole, while `1.05` allows 5% more vertex
+/// transformations in exchange for finer grained sorting.
+///
+/// You must supply a function that can be used to lookup
+/// the position of a vertex.
+///
+/// [`optimize_vertex_cache`]: fn.optimize_vertex_cache.html
+pub fn optimize_overdraw<T, F>(
+    vertices: &[T],
+    triangles: &mut [Triangle<usize>],
+    threshold: f32,
+    mut f: F,
+) where
+    F: FnMut(&T) -> Position,
+{
+    if triangles.is_empty() {
+        return;
+    }
+
+    let hard = hard_boundaries(triangles, vertices.len());
+    let clusters = soft_boundaries(triangles, vertices.len(), &hard, threshold);
+
+    let pos = |f: &mut F, i: usize| Vector3::from(f(&vertices[i]));
+
+    let mut mesh_centroid = Vector3::new(0., 0., 0.);
+    for t in triangles.iter() {
+        mesh_centroid += pos(&mut f, t.x) + pos(&mut f, t.y) + pos(&mut f, t.z);
+    }
+    let mesh_centroid = mesh_centroid * (1. / (triangles.len() * 3) as f32);
+
+    let mut sort_data = Vec::with_capacity(clusters.len());
+    for (i, &start) in clusters.iter().enumerate() {
+        let end = clusters.get(i + 1).copied().unwrap_or(triangles.len());
+
+        let mut area = 0.;
+        let mut centroid = Vector3::new(0., 0., 0.);
+        let mut normal = Vector3::new(0., 0., 0.);
+        for t in &triangles[start..end] {
+            let (a, b, c) = (pos(&mut f, t.x), pos(&mut f, t.y), pos(&mut f, t.z));
+            let n = (b - a).cross(c - a);
+            let a_t = n.magnitude();
+            centroid += (a + b + c) * (a_t / 3.);
+            normal += n;
+            area += a_t;
+        }
+
+        let centroid = if area > 0. {
+            centroid * (1. / area)
+        } else {
+            centroid
+        };
+        let length = normal.magnitude();
+        let normal = if length > 0. {
+            normal * (1. / length)
+        } else {
+            normal
+        };
+
+        sort_data.push((start..end, (centroid - mesh_centroid).dot(normal)));
+    }
+
+    // clusters that face away from the center are drawn first
+    sort_data.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let reordered: Vec<_> = sort_data
+        .iter()
+        .flat_map(|(range, _)| triangles[range.clone()].iter().copied())
+        .collect();
+    triangles.copy_from_slice(&reordered);
+}
+
+// Splits the triangle list wherever a triangle misses the cache with all
+// of its vertices, which usually marks the start of a new patch.
+fn hard_boundaries(triangles: &[Triangle<usize>], vertex_count: usize) -> Vec<usize> {
+    let mut cache = FifoCache::new(vertex_count, FIFO_CACHE_SIZE);
+    let mut boundaries = Vec::new();
+    for (i, t) in triangles.iter().enumerate() {
+        if cache.insert(t) == 3 || i == 0 {
+            boundaries.push(i);
+        }
+    }
+    boundaries
+}
+
+// Splits each hard cluster into smaller ones, each of which reaches the
+// cluster's own cache miss ratio scaled by `threshold`.
+fn soft_boundaries(
+    triangles: &[Triangle<usize>],
+    vertex_count: usize,
+    hard: &[usize],
+    threshold: f32,
+) -> Vec<usize> {
+    let mut cache = FifoCache::new(vertex_count, FIFO_CACHE_SIZE);
+    let mut boundaries = Vec::new();
+
+    for (i, &start) in hard.iter().enumerate() {
+        let end = hard.get(i + 1).copied().unwrap_or(triangles.len());
+        let cluster = &triangles[start..end];
+
+        cache.clear();
+        let misses: usize = cluster.iter().map(|t| cache.insert(t)).sum();
+        let cluster_threshold = threshold * misses as f32 / cluster.len() as f32;
+
+        boundaries.push(start);
+        cache.clear();
+        let (mut running_misses, mut running_faces) = (0, 0);
+        for (j, t) in cluster.iter().enumerate() {
+            running_misses += cache.insert(t);
+            running_faces += 1;
+            if running_misses as f32 / running_faces as f32 <= cluster_threshold {
+                boundaries.push(start + j + 1);
+                cache.clear();
+                running_misses = 0;
+                running_faces = 0;
+            }
+        }
+
+        // the last cluster is usually left with a handful of triangles
+        // and a poor cache miss ratio, so it is merged with the previous
+        if *boundaries.last().unwrap() != start {
+            boundaries.pop();
+        }
+    }
+
+    boundaries
+}
+
+// A FIFO post-transform cache simulation, using timestamps so that the
+// cache can be cleared in constant time.
+struct FifoCache {
+    timestamps: Vec<usize>,
+    timestamp: usize,
+    size: usize,
+}
+
+impl FifoCache {
+    fn new(vertex_count: usize, size: usize) -> Self {
+        FifoCache {
+            timestamps: vec![0; vertex_count],
+            timestamp: size + 1,
+            size,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.timestamp += self.size + 1;
+    }
+
+    // Returns the number of cache misses caused by the triangle.
+    fn insert(&mut self, t: &Triangle<usize>) -> usize {
+        let mut misses = 0;
+        for &v in &[t.x, t.y, t.z] {
+            if self.timestamp - self.timestamps[v] > self.size {
+                self.timestamps[v] = self.timestamp;
+                self.timestamp += 1;
+                misses += 1;
+            }
+        }
+        misses
+    }
+}
@@ -0,0 +1,204 @@
+//! Winding order flips and consistent orientation repair.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use crate::math::Vector3;
+use crate::Polygon::{self, PolyQuad, PolyTri};
+use crate::{EmitVertices, Position, Quad, Triangle};
+
+/// Provides a way to reverse the winding order of a polygon.
+pub trait Flip {
+    /// Reverses the winding order, keeping the first vertex in place.
+    fn flip(self) -> Self;
+}
+
+impl<T> Flip for Triangle<T> {
+    fn flip(self) -> Self {
+        let Triangle { x, y, z } = self;
+        Triangle::new(x, z, y)
+    }
+}
+
+impl<T> Flip for Quad<T> {
+    fn flip(self) -> Self {
+        let Quad { x, y, z, w } = self;
+        Quad::new(x, w, z, y)
+    }
+}
+
+impl<T> Flip for Polygon<T> {
+    fn flip(self) -> Self {
+        match self {
+            PolyTri(t) => PolyTri(t.flip()),
+            PolyQuad(q) => PolyQuad(q.flip()),
+        }
+    }
+}
+
+/// A trait to easily reverse the winding order of every polygon in an
+/// [`Iterator`], turning the polygons inside out.
+pub trait FlipWinding: Sized {
+    /// Reverse the winding order of every polygon.
+    fn flip_winding(self) -> FlipWindingIterator<Self>;
+}
+
+impl<P: Flip, T: Iterator<Item = P>> FlipWinding for T {
+    fn flip_winding(self) -> FlipWindingIterator<Self> {
+        FlipWindingIterator { source: self }
+    }
+}
+
+/// An [`Iterator`] that reverses the winding order of polygons.
+///
+/// This `struct` is created by the [`flip_winding`] method on [`FlipWinding`].
+///
+/// [`flip_winding`]: trait.FlipWinding.html#method.flip_winding
+/// [`FlipWinding`]: trait.FlipWinding.html
+pub struct FlipWindingIterator<SRC> {
+    source: SRC,
+}
+
+impl<P: Flip, SRC: Iterator<Item = P>> Iterator for FlipWindingIterator<SRC> {
+    type Item = P;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.source.size_hint()
+    }
+
+    fn next(&mut self) -> Option<P> {
+        self.source.next().map(Flip::flip)
+    }
+}
+
+/// Flips polygons so that all the polygons connected to each other are
+/// wound the same way, and returns the indices of the flipped polygons.
+///
+/// Within every connected piece of the mesh, the first polygon keeps its
+/// winding, and the rest follow it across the edges shared by exactly two
+/// polygons. On a surface that can not be oriented, like a Möbius strip,
+/// some edges are left inconsistent.
+pub fn orient_consistently<P>(polygons: &mut [P]) -> Vec<usize>
+where
+    P: EmitVertices<usize> + Flip + Clone,
+{
+    let faces = faces(polygons);
+    let mut flipped = vec![false; faces.len()];
+    for (f, flip) in components(&faces).into_iter().flatten() {
+        flipped[f] = flip;
+    }
+    apply(polygons, &flipped)
+}
+
+/// Flips polygons so that all the polygons connected to each other are
+/// wound the same way, and every connected piece faces outward, and
+/// returns the indices of the flipped polygons.
+///
+/// A piece faces outward when its signed volume is positive, which is
+/// only meaningful for closed pieces. `f` looks up the position of a vertex.
+pub fn orient_outward<T, P, F>(vertices: &[T], polygons: &mut [P], mut f: F) -> Vec<usize>
+where
+    P: EmitVertices<usize> + Flip + Clone,
+    F: FnMut(&T) -> Position,
+{
+    let positions: Vec<Vector3> = vertices.iter().map(|v| Vector3::from(f(v))).collect();
+    let faces = faces(polygons);
+    let mut flipped = vec![false; faces.len()];
+
+    for flips in components(&faces) {
+        // six times the signed volume, summed over a fan of every face
+        let mut volume = 0.;
+        for &(f, flip) in &flips {
+            let face = &faces[f];
+            for i in 1..face.len() - 1 {
+                let (b, c) = if flip {
+                    (face[i + 1], face[i])
+                } else {
+                    (face[i], face[i + 1])
+                };
+                volume += positions[face[0]].dot(positions[b].cross(positions[c]));
+            }
+        }
+        for (f, flip) in flips {
+            flipped[f] = flip != (volume < 0.);
+        }
+    }
+    apply(polygons, &flipped)
+}
+
+fn faces<P: EmitVertices<usize> + Clone>(polygons: &[P]) -> Vec<Vec<usize>> {
+    polygons
+        .iter()
+        .map(|p| {
+            let mut face = Vec::with_capacity(4);
+            p.clone().emit_vertices(|v| face.push(v));
+            face
+        })
+        .collect()
+}
+
+// true if `face` runs from `a` straight to `b`
+fn runs(face: &[usize], a: usize, b: usize) -> bool {
+    face.iter()
+        .position(|&v| v == a)
+        .is_some_and(|i| face[(i + 1) % face.len()] == b)
+}
+
+// walks the faces connected by manifold edges, returning every piece as
+// its faces along with whether each has to be flipped to match the first
+fn components(faces: &[Vec<usize>]) -> Vec<Vec<(usize, bool)>> {
+    let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (f, face) in faces.iter().enumerate() {
+        for (i, &a) in face.iter().enumerate() {
+            let b = face[(i + 1) % face.len()];
+            edges.entry((a.min(b), a.max(b))).or_default().push(f);
+        }
+    }
+
+    let mut flip: Vec<Option<bool>> = vec![None; faces.len()];
+    let mut pieces = Vec::new();
+    for seed in 0..faces.len() {
+        if flip[seed].is_some() {
+            continue;
+        }
+        flip[seed] = Some(false);
+        let mut piece = vec![(seed, false)];
+        let mut queue = VecDeque::new();
+        queue.push_back(seed);
+
+        while let Some(f) = queue.pop_front() {
+            let face = &faces[f];
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+                let g = match edges[&(a.min(b), a.max(b))][..] {
+                    [g, h] if g == f => h,
+                    [g, h] if h == f => g,
+                    _ => continue,
+                };
+                if flip[g].is_some() {
+                    continue;
+                }
+                // after flipping, `f` runs from a to b if and only if
+                // `flip[f]` is false, and `g` must run the other way
+                let same = runs(&faces[g], a, b);
+                let flip_g = same != flip[f].unwrap();
+                flip[g] = Some(flip_g);
+                piece.push((g, flip_g));
+                queue.push_back(g);
+            }
+        }
+        pieces.push(piece);
+    }
+    pieces
+}
+
+fn apply<P: Flip + Clone>(polygons: &mut [P], flipped: &[bool]) -> Vec<usize> {
+    let mut indices = Vec::new();
+    for (i, p) in polygons.iter_mut().enumerate() {
+        if flipped[i] {
+            *p = p.clone().flip();
+            indices.push(i);
+        }
+    }
+    indices
+}
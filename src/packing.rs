@@ -0,0 +1,427 @@
+//! Packing vertices into byte buffers described by a vertex layout.
+
+use crate::encoding::{
+    decode_f16, decode_octahedral16, decode_octahedral8, decode_snorm10_10_10_2, decode_unorm16,
+    encode_f16, encode_octahedral16, encode_octahedral8, encode_snorm10_10_10_2, encode_unorm16,
+};
+use crate::{Position, VertexAttributes};
+
+/// A standard vertex attribute, as given by [`VertexAttributes`].
+///
+/// [`VertexAttributes`]: trait.VertexAttributes.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    /// The position, with three components.
+    Position,
+    /// The normal, with three components.
+    Normal,
+    /// The texture coordinate, with two components.
+    TexCoord,
+    /// The tangent, with four components.
+    Tangent,
+}
+
+/// The format an attribute is stored in, with little-endian components.
+///
+/// Besides plain floats, there are compact formats that trade precision for
+/// size. Each of them notes the format the GPU reads it as, and how a
+/// shader decodes it, if it has to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// One 32-bit float.
+    Float32,
+    /// Two 32-bit floats.
+    Float32x2,
+    /// Three 32-bit floats.
+    Float32x3,
+    /// Four 32-bit floats.
+    Float32x4,
+    /// Two 16-bit half floats.
+    Float16x2,
+    /// Four 16-bit half floats.
+    Float16x4,
+    /// Two 16-bit unsigned normalized integers, for components from 0 to 1
+    /// such as texture coordinates. Positions are quantized in their
+    /// bounding box, as with [`Format::Unorm16x4`].
+    ///
+    /// [`Format::Unorm16x4`]: enum.Format.html#variant.Unorm16x4
+    Unorm16x2,
+    /// Four 16-bit unsigned normalized integers. Positions are quantized in
+    /// their bounding box, which the shader scales back up to, as given by
+    /// [`PackedVertices::position_bounds`].
+    ///
+    /// [`PackedVertices::position_bounds`]: struct.PackedVertices.html#structfield.position_bounds
+    Unorm16x4,
+    /// A unit vector mapped onto an octahedron, in two 16-bit signed
+    /// normalized integers, which the shader unfolds.
+    Octahedral16,
+    /// A unit vector mapped onto an octahedron, in two 8-bit signed
+    /// normalized integers, which the shader unfolds.
+    Octahedral8,
+    /// Three 10-bit and one 2-bit signed normalized integers packed in 32
+    /// bits, from the lowest bits up. The last component only keeps its sign.
+    Snorm10_10_10_2,
+}
+
+impl Format {
+    /// The number of components.
+    pub fn components(self) -> usize {
+        match self {
+            Format::Float32 => 1,
+            Format::Float32x2
+            | Format::Float16x2
+            | Format::Unorm16x2
+            | Format::Octahedral16
+            | Format::Octahedral8 => 2,
+            Format::Float32x3 => 3,
+            Format::Float32x4 | Format::Float16x4 | Format::Unorm16x4 | Format::Snorm10_10_10_2 => {
+                4
+            }
+        }
+    }
+
+    /// The size in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Format::Float32 | Format::Float32x2 | Format::Float32x3 | Format::Float32x4 => {
+                4 * self.components()
+            }
+            Format::Float16x2 | Format::Float16x4 | Format::Unorm16x2 | Format::Unorm16x4 => {
+                2 * self.components()
+            }
+            Format::Octahedral16 | Format::Snorm10_10_10_2 => 4,
+            Format::Octahedral8 => 2,
+        }
+    }
+
+    fn is_unorm(self) -> bool {
+        self == Format::Unorm16x2 || self == Format::Unorm16x4
+    }
+
+    // the number of components of the attribute it stores, after decoding
+    fn decoded_components(self) -> usize {
+        match self {
+            Format::Octahedral16 | Format::Octahedral8 => 3,
+            _ => self.components(),
+        }
+    }
+}
+
+/// Where and how one attribute is stored, as part of a [`VertexLayout`].
+///
+/// [`VertexLayout`]: struct.VertexLayout.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AttributeLayout {
+    /// The attribute stored.
+    pub attribute: Attribute,
+    /// The format it is stored in.
+    pub format: Format,
+    /// The buffer it is stored in.
+    pub buffer: usize,
+    /// The offset in bytes of the attribute of the first vertex.
+    pub offset: usize,
+    /// The distance in bytes between the attributes of two vertices.
+    pub stride: usize,
+}
+
+/// Describes how vertices are packed into byte buffers.
+///
+/// The layout is plain data, so it can be used to set up the vertex state
+/// of a graphics pipeline. Every offset and stride is a multiple of 4 bytes.
+///
+/// ```
+/// use genmesh::generators::{Cube, SharedVertex};
+/// use genmesh::{Attribute, Format, VertexLayout};
+///
+/// let layout = VertexLayout::interleaved(&[
+///     (Attribute::Position, Format::Float32x3),
+///     (Attribute::Normal, Format::Float32x3),
+/// ]);
+/// assert_eq!(layout.stride(0), 24);
+/// assert_eq!(layout.attributes[1].offset, 12);
+///
+/// let vertices: Vec<_> = Cube::new().shared_vertex_iter().collect();
+/// let packed = layout.pack(&vertices);
+/// assert_eq!(packed.buffers[0].len(), 24 * vertices.len());
+/// assert_eq!(packed.max_errors, vec![0., 0.]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexLayout {
+    /// The stored attributes.
+    pub attributes: Vec<AttributeLayout>,
+}
+
+fn align(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+impl VertexLayout {
+    /// Creates a layout that stores all the attributes of a vertex next to
+    /// each other, in a single buffer.
+    pub fn interleaved(attributes: &[(Attribute, Format)]) -> Self {
+        let mut offset = 0;
+        let mut layout: Vec<AttributeLayout> = attributes
+            .iter()
+            .map(|&(attribute, format)| {
+                let a = AttributeLayout {
+                    attribute,
+                    format,
+                    buffer: 0,
+                    offset,
+                    stride: 0,
+                };
+                offset = align(offset + format.size());
+                a
+            })
+            .collect();
+        for a in &mut layout {
+            a.stride = offset;
+        }
+        VertexLayout { attributes: layout }
+    }
+
+    /// Creates a layout that stores every attribute in a buffer of its
+    /// own, in the order they are given.
+    pub fn planar(attributes: &[(Attribute, Format)]) -> Self {
+        VertexLayout {
+            attributes: attributes
+                .iter()
+                .enumerate()
+                .map(|(buffer, &(attribute, format))| AttributeLayout {
+                    attribute,
+                    format,
+                    buffer,
+                    offset: 0,
+                    stride: align(format.size()),
+                })
+                .collect(),
+        }
+    }
+
+    /// The number of buffers the vertices are packed into.
+    pub fn buffer_count(&self) -> usize {
+        self.attributes
+            .iter()
+            .map(|a| a.buffer + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The stride of a buffer, which is zero for buffers that store no
+    /// attributes.
+    pub fn stride(&self, buffer: usize) -> usize {
+        self.attributes
+            .iter()
+            .filter(|a| a.buffer == buffer)
+            .map(|a| a.stride)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Packs `vertices` into one buffer for each of the buffers of the
+    /// layout, measuring the error of the compact formats.
+    ///
+    /// Attributes that a vertex does not have are stored as zeros. Extra
+    /// components are filled with zeros too, except for the fourth of a
+    /// position, which is 1. Components past the end of the format are
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if an attribute does not fit in its stride,
+    /// which can only happen with a layout built by hand.
+    pub fn pack<V: VertexAttributes>(&self, vertices: &[V]) -> PackedVertices {
+        for a in &self.attributes {
+            assert!(
+                a.offset + a.format.size() <= a.stride,
+                "{:?} at offset {} does not fit in a stride of {} bytes",
+                a.attribute,
+                a.offset,
+                a.stride
+            );
+        }
+
+        let mut buffers: Vec<Vec<u8>> = (0..self.buffer_count())
+            .map(|b| vec![0; self.stride(b) * vertices.len()])
+            .collect();
+        let quantized = self
+            .attributes
+            .iter()
+            .any(|a| a.attribute == Attribute::Position && a.format.is_unorm());
+        let position_bounds = if quantized { bounds(vertices) } else { None };
+
+        let mut max_errors = Vec::with_capacity(self.attributes.len());
+        for a in &self.attributes {
+            let buffer = &mut buffers[a.buffer];
+            let bounds = match a.attribute {
+                Attribute::Position => position_bounds,
+                _ => None,
+            };
+            let compared = a.format.decoded_components().min(match a.attribute {
+                Attribute::Position | Attribute::Normal => 3,
+                Attribute::TexCoord => 2,
+                Attribute::Tangent => 4,
+            });
+
+            let mut max_error = 0f32;
+            for (i, v) in vertices.iter().enumerate() {
+                let start = a.offset + i * a.stride;
+                let out = &mut buffer[start..start + a.format.size()];
+                let values = components(v, a.attribute);
+                write(out, a.format, &values, bounds);
+                let decoded = read(out, a.format, bounds);
+                let error: f32 = (0..compared)
+                    .map(|c| (decoded[c] - values[c]).powi(2))
+                    .sum();
+                max_error = max_error.max(error.sqrt());
+            }
+            max_errors.push(max_error);
+        }
+
+        PackedVertices {
+            buffers,
+            position_bounds,
+            max_errors,
+        }
+    }
+}
+
+/// Vertices packed by a [`VertexLayout`].
+///
+/// [`VertexLayout`]: struct.VertexLayout.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackedVertices {
+    /// The packed bytes, one buffer for each of the buffers of the layout.
+    pub buffers: Vec<Vec<u8>>,
+    /// The smallest and largest corner of the box that positions stored as
+    /// [`Format::Unorm16x2`] or [`Format::Unorm16x4`] are quantized in. A
+    /// position is decoded as `min + value * (max - min)`.
+    ///
+    /// [`Format::Unorm16x2`]: enum.Format.html#variant.Unorm16x2
+    /// [`Format::Unorm16x4`]: enum.Format.html#variant.Unorm16x4
+    pub position_bounds: Option<(Position, Position)>,
+    /// The largest error of each of the attributes of the layout, as the
+    /// distance between the original and the decoded values. Components
+    /// that the format does not store are not counted.
+    pub max_errors: Vec<f32>,
+}
+
+fn bounds<V: VertexAttributes>(vertices: &[V]) -> Option<(Position, Position)> {
+    let mut positions = vertices.iter().map(|v| v.position());
+    let first = positions.next()?;
+    let (mut min, mut max) = (first, first);
+    for p in positions {
+        min = [min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)].into();
+        max = [max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)].into();
+    }
+    Some((min, max))
+}
+
+// the four components of an attribute of a vertex, padded as a position
+// would be in homogeneous coordinates
+fn components<V: VertexAttributes>(v: &V, attribute: Attribute) -> [f32; 4] {
+    match attribute {
+        Attribute::Position => {
+            let p = v.position();
+            [p.x, p.y, p.z, 1.]
+        }
+        Attribute::Normal => v.normal().map_or([0.; 4], |n| [n.x, n.y, n.z, 0.]),
+        Attribute::TexCoord => v.tex_coord().map_or([0.; 4], |t| [t.x, t.y, 0., 0.]),
+        Attribute::Tangent => v.tangent().map_or([0.; 4], |t| [t.x, t.y, t.z, t.w]),
+    }
+}
+
+// values relative to the bounds, which are only given for quantized positions
+fn normalized(values: &[f32; 4], bounds: Option<(Position, Position)>) -> [f32; 4] {
+    match bounds {
+        Some((min, max)) => {
+            let axis = |value: f32, min: f32, max: f32| {
+                if max > min {
+                    (value - min) / (max - min)
+                } else {
+                    0.
+                }
+            };
+            [
+                axis(values[0], min.x, max.x),
+                axis(values[1], min.y, max.y),
+                axis(values[2], min.z, max.z),
+                values[3],
+            ]
+        }
+        None => *values,
+    }
+}
+
+fn write(out: &mut [u8], format: Format, values: &[f32; 4], bounds: Option<(Position, Position)>) {
+    match format {
+        Format::Float32 | Format::Float32x2 | Format::Float32x3 | Format::Float32x4 => {
+            for (chunk, value) in out.chunks_mut(4).zip(values) {
+                chunk.copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        Format::Float16x2 | Format::Float16x4 => {
+            for (chunk, &value) in out.chunks_mut(2).zip(values) {
+                chunk.copy_from_slice(&encode_f16(value).to_le_bytes());
+            }
+        }
+        Format::Unorm16x2 | Format::Unorm16x4 => {
+            for (chunk, &value) in out.chunks_mut(2).zip(&normalized(values, bounds)) {
+                chunk.copy_from_slice(&encode_unorm16(value).to_le_bytes());
+            }
+        }
+        Format::Octahedral16 => {
+            let [x, y] = encode_octahedral16([values[0], values[1], values[2]].into());
+            out[..2].copy_from_slice(&x.to_le_bytes());
+            out[2..].copy_from_slice(&y.to_le_bytes());
+        }
+        Format::Octahedral8 => {
+            let [x, y] = encode_octahedral8([values[0], values[1], values[2]].into());
+            out.copy_from_slice(&[x as u8, y as u8]);
+        }
+        Format::Snorm10_10_10_2 => {
+            out.copy_from_slice(&encode_snorm10_10_10_2(*values).to_le_bytes());
+        }
+    }
+}
+
+fn read(data: &[u8], format: Format, bounds: Option<(Position, Position)>) -> [f32; 4] {
+    let mut values = [0.; 4];
+    match format {
+        Format::Float32 | Format::Float32x2 | Format::Float32x3 | Format::Float32x4 => {
+            for (value, c) in values.iter_mut().zip(data.chunks(4)) {
+                *value = f32::from_le_bytes([c[0], c[1], c[2], c[3]]);
+            }
+        }
+        Format::Float16x2 | Format::Float16x4 => {
+            for (value, c) in values.iter_mut().zip(data.chunks(2)) {
+                *value = decode_f16(u16::from_le_bytes([c[0], c[1]]));
+            }
+        }
+        Format::Unorm16x2 | Format::Unorm16x4 => {
+            for (value, c) in values.iter_mut().zip(data.chunks(2)) {
+                *value = decode_unorm16(u16::from_le_bytes([c[0], c[1]]));
+            }
+            if let Some((min, max)) = bounds {
+                values[0] = min.x + values[0] * (max.x - min.x);
+                values[1] = min.y + values[1] * (max.y - min.y);
+                values[2] = min.z + values[2] * (max.z - min.z);
+            }
+        }
+        Format::Octahedral16 => {
+            let x = i16::from_le_bytes([data[0], data[1]]);
+            let y = i16::from_le_bytes([data[2], data[3]]);
+            let n = decode_octahedral16([x, y]);
+            values[..3].copy_from_slice(&[n.x, n.y, n.z]);
+        }
+        Format::Octahedral8 => {
+            let n = decode_octahedral8([data[0] as i8, data[1] as i8]);
+            values[..3].copy_from_slice(&[n.x, n.y, n.z]);
+        }
+        Format::Snorm10_10_10_2 => {
+            values =
+                decode_snorm10_10_10_2(u32::from_le_bytes([data[0], data[1], data[2], data[3]]));
+        }
+    }
+    values
+}
@@ -0,0 +1,619 @@
+//! Reading and writing PLY files, in ASCII and binary form.
+//!
+//! The vertex properties of a PLY file are free-form, so vertex types
+//! describe how they map onto them by implementing [`PlyVertex`]. Faces are
+//! stored in a `vertex_indices` list property of the `face` element, and
+//! any other element or property is skipped when reading.
+//!
+//! ```
+//! use genmesh::generators::{IcoSphere, IndexedPolygon, SharedVertex};
+//! use genmesh::ply::{self, PlyFormat};
+//! use genmesh::{Polygon, Vertex};
+//!
+//! let sphere = IcoSphere::new();
+//! let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+//! let triangles: Vec<_> = sphere.indexed_polygon_iter().collect();
+//!
+//! let mut data = Vec::new();
+//! ply::write(&mut data, PlyFormat::BinaryLittleEndian, &vertices, &triangles).unwrap();
+//! let (read, polygons): (Vec<Vertex>, _) = ply::read(&data[..]).unwrap();
+//! assert_eq!(read, vertices);
+//! assert_eq!(polygons[0], Polygon::PolyTri(triangles[0]));
+//! ```
+//!
+//! [`PlyVertex`]: trait.PlyVertex.html
+
+use std::io::{self, BufRead, Write};
+
+use crate::obj::emit_face;
+use crate::{EmitVertices, Polygon, Position, ReadError, Vertex};
+
+/// The scalar types of PLY properties.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlyType {
+    /// A signed 8-bit integer, `char` or `int8`.
+    Char,
+    /// An unsigned 8-bit integer, `uchar` or `uint8`.
+    UChar,
+    /// A signed 16-bit integer, `short` or `int16`.
+    Short,
+    /// An unsigned 16-bit integer, `ushort` or `uint16`.
+    UShort,
+    /// A signed 32-bit integer, `int` or `int32`.
+    Int,
+    /// An unsigned 32-bit integer, `uint` or `uint32`.
+    UInt,
+    /// A 32-bit float, `float` or `float32`.
+    Float,
+    /// A 64-bit float, `double` or `float64`.
+    Double,
+}
+
+impl PlyType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => PlyType::Char,
+            "uchar" | "uint8" => PlyType::UChar,
+            "short" | "int16" => PlyType::Short,
+            "ushort" | "uint16" => PlyType::UShort,
+            "int" | "int32" => PlyType::Int,
+            "uint" | "uint32" => PlyType::UInt,
+            "float" | "float32" => PlyType::Float,
+            "double" | "float64" => PlyType::Double,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PlyType::Char => "char",
+            PlyType::UChar => "uchar",
+            PlyType::Short => "short",
+            PlyType::UShort => "ushort",
+            PlyType::Int => "int",
+            PlyType::UInt => "uint",
+            PlyType::Float => "float",
+            PlyType::Double => "double",
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            PlyType::Char | PlyType::UChar => 1,
+            PlyType::Short | PlyType::UShort => 2,
+            PlyType::Int | PlyType::UInt | PlyType::Float => 4,
+            PlyType::Double => 8,
+        }
+    }
+
+    fn is_integer(self) -> bool {
+        self != PlyType::Float && self != PlyType::Double
+    }
+
+    // rounds and clamps a value into the range of an integer type, so every
+    // format writes the same number
+    fn fit(self, value: f64) -> f64 {
+        let (min, max) = match self {
+            PlyType::Char => (i8::MIN as f64, i8::MAX as f64),
+            PlyType::UChar => (0., u8::MAX as f64),
+            PlyType::Short => (i16::MIN as f64, i16::MAX as f64),
+            PlyType::UShort => (0., u16::MAX as f64),
+            PlyType::Int => (i32::MIN as f64, i32::MAX as f64),
+            PlyType::UInt => (0., u32::MAX as f64),
+            PlyType::Float | PlyType::Double => return value,
+        };
+        if value.is_nan() {
+            0.
+        } else {
+            value.round().clamp(min, max)
+        }
+    }
+}
+
+/// The encoding of a PLY file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlyFormat {
+    /// Human readable text.
+    Ascii,
+    /// Binary, with little-endian numbers.
+    BinaryLittleEndian,
+    /// Binary, with big-endian numbers.
+    BinaryBigEndian,
+}
+
+/// Maps a vertex type onto the properties of the PLY `vertex` element.
+///
+/// Property values are passed around as `f64`, which holds every PLY
+/// scalar type exactly. This is implemented for [`Vertex`], as `x`, `y`,
+/// `z`, `nx`, `ny` and `nz`, and for [`Position`], as `x`, `y` and `z`.
+///
+/// ```
+/// use genmesh::ply::{PlyType, PlyVertex};
+///
+/// struct Colored {
+///     pos: [f32; 3],
+///     color: [u8; 3],
+/// }
+///
+/// impl PlyVertex for Colored {
+///     fn properties() -> Vec<(&'static str, PlyType)> {
+///         vec![
+///             ("x", PlyType::Float),
+///             ("y", PlyType::Float),
+///             ("z", PlyType::Float),
+///             ("red", PlyType::UChar),
+///             ("green", PlyType::UChar),
+///             ("blue", PlyType::UChar),
+///         ]
+///     }
+///
+///     fn to_properties(&self, values: &mut [f64]) {
+///         for i in 0..3 {
+///             values[i] = self.pos[i] as f64;
+///             values[i + 3] = self.color[i] as f64;
+///         }
+///     }
+///
+///     fn from_properties<F: Fn(&str) -> Option<f64>>(get: F) -> Option<Self> {
+///         Some(Colored {
+///             pos: [get("x")? as f32, get("y")? as f32, get("z")? as f32],
+///             color: [
+///                 get("red").unwrap_or(255.) as u8,
+///                 get("green").unwrap_or(255.) as u8,
+///                 get("blue").unwrap_or(255.) as u8,
+///             ],
+///         })
+///     }
+/// }
+/// ```
+///
+/// [`Vertex`]: ../struct.Vertex.html
+/// [`Position`]: ../type.Position.html
+pub trait PlyVertex: Sized {
+    /// The names and types of the properties written for every vertex.
+    fn properties() -> Vec<(&'static str, PlyType)>;
+
+    /// Stores the property values of the vertex into `values`, in the
+    /// order of `properties`.
+    fn to_properties(&self, values: &mut [f64]);
+
+    /// Builds a vertex from the properties read from a file, looking them
+    /// up by name. Returns `None` if a required property is missing.
+    fn from_properties<F: Fn(&str) -> Option<f64>>(get: F) -> Option<Self>;
+}
+
+impl PlyVertex for Vertex {
+    fn properties() -> Vec<(&'static str, PlyType)> {
+        ["x", "y", "z", "nx", "ny", "nz"]
+            .iter()
+            .map(|&name| (name, PlyType::Float))
+            .collect()
+    }
+
+    fn to_properties(&self, values: &mut [f64]) {
+        let Vertex { pos: p, normal: n } = self;
+        for (value, &c) in values.iter_mut().zip(&[p.x, p.y, p.z, n.x, n.y, n.z]) {
+            *value = c as f64;
+        }
+    }
+
+    fn from_properties<F: Fn(&str) -> Option<f64>>(get: F) -> Option<Self> {
+        let normal = |name| get(name).unwrap_or(0.) as f32;
+        Some(Vertex {
+            pos: Position::from_properties(&get)?,
+            normal: [normal("nx"), normal("ny"), normal("nz")].into(),
+        })
+    }
+}
+
+impl PlyVertex for Position {
+    fn properties() -> Vec<(&'static str, PlyType)> {
+        vec![
+            ("x", PlyType::Float),
+            ("y", PlyType::Float),
+            ("z", PlyType::Float),
+        ]
+    }
+
+    fn to_properties(&self, values: &mut [f64]) {
+        values[0] = self.x as f64;
+        values[1] = self.y as f64;
+        values[2] = self.z as f64;
+    }
+
+    fn from_properties<F: Fn(&str) -> Option<f64>>(get: F) -> Option<Self> {
+        Some([get("x")? as f32, get("y")? as f32, get("z")? as f32].into())
+    }
+}
+
+/// Writes an indexed mesh to a PLY file.
+pub fn write<W, V, P>(
+    mut out: W,
+    format: PlyFormat,
+    vertices: &[V],
+    polygons: &[P],
+) -> io::Result<()>
+where
+    W: Write,
+    V: PlyVertex,
+    P: EmitVertices<usize> + Clone,
+{
+    let format_name = match format {
+        PlyFormat::Ascii => "ascii",
+        PlyFormat::BinaryLittleEndian => "binary_little_endian",
+        PlyFormat::BinaryBigEndian => "binary_big_endian",
+    };
+    let properties = V::properties();
+    writeln!(out, "ply")?;
+    writeln!(out, "format {} 1.0", format_name)?;
+    writeln!(out, "comment written by genmesh")?;
+    writeln!(out, "element vertex {}", vertices.len())?;
+    for (name, ty) in &properties {
+        writeln!(out, "property {} {}", ty.name(), name)?;
+    }
+    writeln!(out, "element face {}", polygons.len())?;
+    writeln!(out, "property list uchar uint vertex_indices")?;
+    writeln!(out, "end_header")?;
+
+    let big_endian = format == PlyFormat::BinaryBigEndian;
+    let mut values = vec![0.; properties.len()];
+    for v in vertices {
+        v.to_properties(&mut values);
+        let types = properties.iter().map(|&(_, ty)| ty);
+        match format {
+            PlyFormat::Ascii => {
+                for (i, (ty, &value)) in types.zip(&values).enumerate() {
+                    let separator = if i == 0 { "" } else { " " };
+                    match ty {
+                        PlyType::Float => write!(out, "{}{}", separator, value as f32)?,
+                        ty if ty.is_integer() => write!(out, "{}{}", separator, ty.fit(value))?,
+                        _ => write!(out, "{}{}", separator, value)?,
+                    }
+                }
+                writeln!(out)?;
+            }
+            _ => {
+                for (ty, &value) in types.zip(&values) {
+                    write_binary(&mut out, ty, value, big_endian)?;
+                }
+            }
+        }
+    }
+
+    let mut face = Vec::with_capacity(4);
+    for p in polygons {
+        face.clear();
+        p.clone().emit_vertices(|v| face.push(v));
+        match format {
+            PlyFormat::Ascii => {
+                write!(out, "{}", face.len())?;
+                for v in &face {
+                    write!(out, " {}", v)?;
+                }
+                writeln!(out)?;
+            }
+            _ => {
+                write_binary(&mut out, PlyType::UChar, face.len() as f64, big_endian)?;
+                for &v in &face {
+                    write_binary(&mut out, PlyType::UInt, v as f64, big_endian)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_binary<W: Write>(
+    out: &mut W,
+    ty: PlyType,
+    value: f64,
+    big_endian: bool,
+) -> io::Result<()> {
+    let value = ty.fit(value);
+    let mut bytes = match ty {
+        PlyType::Char => (value as i8).to_le_bytes().to_vec(),
+        PlyType::UChar => (value as u8).to_le_bytes().to_vec(),
+        PlyType::Short => (value as i16).to_le_bytes().to_vec(),
+        PlyType::UShort => (value as u16).to_le_bytes().to_vec(),
+        PlyType::Int => (value as i32).to_le_bytes().to_vec(),
+        PlyType::UInt => (value as u32).to_le_bytes().to_vec(),
+        PlyType::Float => (value as f32).to_le_bytes().to_vec(),
+        PlyType::Double => value.to_le_bytes().to_vec(),
+    };
+    if big_endian {
+        bytes.reverse();
+    }
+    out.write_all(&bytes)
+}
+
+#[derive(Debug)]
+enum Kind {
+    Scalar(PlyType),
+    List(PlyType, PlyType),
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<(String, Kind)>,
+}
+
+// a source of property values, either text or binary
+trait Values {
+    fn scalar(&mut self, ty: PlyType) -> Result<f64, ReadError>;
+    fn end_element(&mut self) -> Result<(), ReadError>;
+    // the line being read, or 0 for binary data
+    fn line(&self) -> usize;
+}
+
+/// Reads an indexed mesh from a PLY file. Faces with more than four
+/// vertices are split into a fan of triangles.
+pub fn read<R, V>(mut input: R) -> Result<(Vec<V>, Vec<Polygon<usize>>), ReadError>
+where
+    R: BufRead,
+    V: PlyVertex,
+{
+    let (format, elements, number) = read_header(&mut input)?;
+    let mut body = Vec::new();
+    input.read_to_end(&mut body)?;
+
+    match format {
+        PlyFormat::Ascii => {
+            let text = std::str::from_utf8(&body)
+                .map_err(|_| ReadError::parse(number + 1, "body is not text"))?;
+            let mut values = TextValues {
+                lines: text.lines(),
+                words: Vec::new(),
+                number,
+                started: false,
+            };
+            read_body(&elements, &mut values)
+        }
+        PlyFormat::BinaryLittleEndian | PlyFormat::BinaryBigEndian => {
+            let mut values = BinaryValues {
+                data: &body,
+                big_endian: format == PlyFormat::BinaryBigEndian,
+            };
+            read_body(&elements, &mut values)
+        }
+    }
+}
+
+fn read_header<R: BufRead>(input: &mut R) -> Result<(PlyFormat, Vec<Element>, usize), ReadError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut number = 0;
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        number += 1;
+        if input.read_until(b'\n', &mut line)? == 0 {
+            return Err(ReadError::parse(number, "missing end_header"));
+        }
+        let text = std::str::from_utf8(&line)
+            .map_err(|_| ReadError::parse(number, "header is not text"))?;
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let error = |message: &str| Err(ReadError::parse(number, message));
+
+        if number == 1 {
+            if words != ["ply"] {
+                return error("not a PLY file");
+            }
+            continue;
+        }
+        match words[..] {
+            ["format", name, "1.0"] => {
+                format = Some(match name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => return error("unknown format"),
+                });
+            }
+            ["element", name, count] => {
+                let count = match count.parse() {
+                    Ok(count) => count,
+                    Err(_) => return error("bad element count"),
+                };
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            ["property", "list", count, item, name] => {
+                let kind = match (PlyType::parse(count), PlyType::parse(item)) {
+                    (Some(count), Some(item)) if count.is_integer() => Kind::List(count, item),
+                    _ => return error("bad list property type"),
+                };
+                match elements.last_mut() {
+                    Some(element) => element.properties.push((name.to_string(), kind)),
+                    None => return error("property outside an element"),
+                }
+            }
+            ["property", ty, name] => {
+                let kind = match PlyType::parse(ty) {
+                    Some(ty) => Kind::Scalar(ty),
+                    None => return error("bad property type"),
+                };
+                match elements.last_mut() {
+                    Some(element) => element.properties.push((name.to_string(), kind)),
+                    None => return error("property outside an element"),
+                }
+            }
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return error("unexpected header line"),
+        }
+    }
+
+    if let Some(e) = elements
+        .iter()
+        .find(|e| e.count > 0 && e.properties.is_empty())
+    {
+        return Err(ReadError::parse(
+            number,
+            format!("element {} has no properties", e.name),
+        ));
+    }
+    match format {
+        Some(format) => Ok((format, elements, number)),
+        None => Err(ReadError::parse(number, "missing format")),
+    }
+}
+
+fn read_body<V: PlyVertex>(
+    elements: &[Element],
+    values: &mut dyn Values,
+) -> Result<(Vec<V>, Vec<Polygon<usize>>), ReadError> {
+    let mut vertices = Vec::new();
+    // the vertex indices of every face, with the line it was read from
+    let mut faces: Vec<(usize, Vec<f64>)> = Vec::new();
+    let mut scalars = Vec::new();
+    let mut list = Vec::new();
+
+    for element in elements {
+        for _ in 0..element.count {
+            scalars.clear();
+            for (name, kind) in &element.properties {
+                match *kind {
+                    Kind::Scalar(ty) => scalars.push((name.as_str(), values.scalar(ty)?)),
+                    Kind::List(count_type, item_type) => {
+                        let count = values.scalar(count_type)?;
+                        list.clear();
+                        for _ in 0..count.max(0.) as usize {
+                            list.push(values.scalar(item_type)?);
+                        }
+                        if element.name == "face"
+                            && (name == "vertex_indices" || name == "vertex_index")
+                        {
+                            faces.push((values.line(), list.clone()));
+                        }
+                    }
+                }
+            }
+            values.end_element()?;
+
+            if element.name == "vertex" {
+                let get = |name: &str| scalars.iter().find(|s| s.0 == name).map(|s| s.1);
+                match V::from_properties(get) {
+                    Some(v) => vertices.push(v),
+                    None => {
+                        return Err(ReadError::parse(
+                            values.line(),
+                            "vertex is missing properties",
+                        ))
+                    }
+                }
+            }
+        }
+    }
+
+    let mut polygons = Vec::with_capacity(faces.len());
+    for (line, face) in faces {
+        let face = face
+            .iter()
+            .map(|&i| {
+                if i >= 0. && i.fract() == 0. && (i as usize) < vertices.len() {
+                    Ok(i as usize)
+                } else {
+                    Err(ReadError::parse(line, format!("bad vertex index {}", i)))
+                }
+            })
+            .collect::<Result<Vec<usize>, _>>()?;
+        if face.len() < 3 {
+            return Err(ReadError::parse(line, "face has fewer than 3 vertices"));
+        }
+        emit_face(&face, &mut polygons);
+    }
+    Ok((vertices, polygons))
+}
+
+struct TextValues<'a> {
+    lines: std::str::Lines<'a>,
+    words: Vec<&'a str>,
+    number: usize,
+    started: bool,
+}
+
+impl<'a> Values for TextValues<'a> {
+    fn scalar(&mut self, ty: PlyType) -> Result<f64, ReadError> {
+        while !self.started {
+            match self.lines.next() {
+                Some(line) => {
+                    self.number += 1;
+                    self.words = line.split_whitespace().rev().collect();
+                    self.started = !self.words.is_empty();
+                }
+                None => return Err(ReadError::parse(self.number, "unexpected end of file")),
+            }
+        }
+        let word = match self.words.pop() {
+            Some(word) => word,
+            None => return Err(ReadError::parse(self.number, "too few values")),
+        };
+        let value: f64 = word
+            .parse()
+            .map_err(|_| ReadError::parse(self.number, format!("bad value {}", word)))?;
+        if ty.is_integer() && value.fract() != 0. {
+            return Err(ReadError::parse(
+                self.number,
+                format!("bad integer {}", word),
+            ));
+        }
+        Ok(value)
+    }
+
+    fn end_element(&mut self) -> Result<(), ReadError> {
+        if !self.words.is_empty() {
+            return Err(ReadError::parse(self.number, "too many values"));
+        }
+        self.started = false;
+        Ok(())
+    }
+
+    fn line(&self) -> usize {
+        self.number
+    }
+}
+
+struct BinaryValues<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Values for BinaryValues<'a> {
+    fn scalar(&mut self, ty: PlyType) -> Result<f64, ReadError> {
+        let size = ty.size();
+        if self.data.len() < size {
+            return Err(ReadError::parse(0, "unexpected end of data"));
+        }
+        let mut bytes = [0u8; 8];
+        bytes[..size].copy_from_slice(&self.data[..size]);
+        self.data = &self.data[size..];
+        if self.big_endian {
+            bytes[..size].reverse();
+        }
+        let [a, b, c, d, ..] = bytes;
+        Ok(match ty {
+            PlyType::Char => a as i8 as f64,
+            PlyType::UChar => a as f64,
+            PlyType::Short => i16::from_le_bytes([a, b]) as f64,
+            PlyType::UShort => u16::from_le_bytes([a, b]) as f64,
+            PlyType::Int => i32::from_le_bytes([a, b, c, d]) as f64,
+            PlyType::UInt => u32::from_le_bytes([a, b, c, d]) as f64,
+            PlyType::Float => f32::from_le_bytes([a, b, c, d]) as f64,
+            PlyType::Double => f64::from_le_bytes(bytes),
+        })
+    }
+
+    fn end_element(&mut self) -> Result<(), ReadError> {
+        Ok(())
+    }
+
+    fn line(&self) -> usize {
+        0
+    }
+}
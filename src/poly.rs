@@ -61,6 +61,18 @@ pub enum Polygon<T> {
     PolyQuad(Quad<T>),
 }
 
+impl<T> From<Triangle<T>> for Polygon<T> {
+    fn from(t: Triangle<T>) -> Self {
+        Polygon::PolyTri(t)
+    }
+}
+
+impl<T> From<Quad<T>> for Polygon<T> {
+    fn from(q: Quad<T>) -> Self {
+        Polygon::PolyQuad(q)
+    }
+}
+
 /// The core mechanism of the [`Vertices`] trait. This is a mechanism for unwrapping
 /// a polygon extracting all of the vertices that it bound together.
 ///
@@ -297,15 +309,8 @@ pub struct MapToVerticesIter<SRC, T, U, F: FnMut(T) -> U> {
     phantom: PhantomData<(T, U)>,
 }
 
-impl<
-        'a,
-        P,
-        POut: MapVertex<T, U, Output = P>,
-        SRC: Iterator<Item = POut>,
-        T,
-        U,
-        F: FnMut(T) -> U,
-    > Iterator for MapToVerticesIter<SRC, T, U, F>
+impl<P, POut: MapVertex<T, U, Output = P>, SRC: Iterator<Item = POut>, T, U, F: FnMut(T) -> U>
+    Iterator for MapToVerticesIter<SRC, T, U, F>
 {
     type Item = P;
 
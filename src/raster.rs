@@ -0,0 +1,370 @@
+//! A small software rasterizer for rendering meshes without a GPU, and
+//! the images it renders into.
+//!
+//! Triangles are drawn with a z-buffer and Lambert shading from the vertex
+//! normals, interpolated across each triangle. Images are written and read
+//! as binary PPM and PGM files, and can be compared with a tolerance, which
+//! makes them suited to golden image tests.
+//!
+//! ```
+//! use genmesh::generators::SphereUv;
+//! use genmesh::raster::Rasterizer;
+//! use genmesh::Camera;
+//!
+//! let camera = Camera::new([0., -5., 0.].into(), [0., 0., 0.].into());
+//! let image = Rasterizer::new(32, 32, camera).render(SphereUv::new(16, 8));
+//! // the middle of the sphere faces the light
+//! assert!(image.pixel(16, 16)[0] > 240);
+//! assert_eq!(image.pixel(0, 0), [0, 0, 0]);
+//!
+//! let mut pgm = Vec::new();
+//! image.write_pgm(&mut pgm).unwrap();
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::math::Vector3;
+use crate::{Camera, EmitTriangles, Position, ReadError, VertexAttributes};
+
+/// An RGB image with 8 bits per channel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// Creates a new `Image` filled with `color`.
+    pub fn new(width: usize, height: usize, color: [u8; 3]) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The color of a pixel, counting rows from the top.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+
+    /// The pixels of the image, row by row from the top.
+    pub fn pixels(&self) -> &[[u8; 3]] {
+        &self.pixels
+    }
+
+    /// Counts the pixels that differ from `other` by more than `tolerance`
+    /// in any channel. Images of different sizes differ in every pixel.
+    pub fn count_differences(&self, other: &Image, tolerance: u8) -> usize {
+        if (self.width, self.height) != (other.width, other.height) {
+            return self.pixels.len().max(other.pixels.len());
+        }
+        self.pixels
+            .iter()
+            .zip(&other.pixels)
+            .filter(|(a, b)| {
+                a.iter()
+                    .zip(b.iter())
+                    .any(|(a, b)| a.abs_diff(*b) > tolerance)
+            })
+            .count()
+    }
+
+    /// Writes the image as a binary PPM file.
+    pub fn write_ppm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for p in &self.pixels {
+            out.write_all(p)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the luminance of the image as a binary PGM file.
+    pub fn write_pgm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P5\n{} {}\n255\n", self.width, self.height)?;
+        let gray: Vec<u8> = self.pixels.iter().map(|&p| luminance(p)).collect();
+        out.write_all(&gray)
+    }
+
+    /// Reads a binary PPM or PGM file with 8 bits per channel. Gray pixels
+    /// are copied into all three channels.
+    pub fn read_pnm<R: Read>(mut input: R) -> Result<Image, ReadError> {
+        let mut data = Vec::new();
+        input.read_to_end(&mut data)?;
+
+        // the magic number, width, height and maximum value, separated by
+        // whitespace and comments, and a single whitespace before the pixels
+        let mut fields = Vec::new();
+        let mut i = 0;
+        while fields.len() < 4 {
+            while i < data.len() && data[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i < data.len() && data[i] == b'#' {
+                while i < data.len() && data[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            let start = i;
+            while i < data.len() && !data[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if start == i {
+                return Err(ReadError::parse(1, "incomplete header"));
+            }
+            fields.push(String::from_utf8_lossy(&data[start..i]).into_owned());
+        }
+        let channels = match &fields[0][..] {
+            "P5" => 1,
+            "P6" => 3,
+            magic => return Err(ReadError::parse(1, format!("unsupported format {}", magic))),
+        };
+        let number = |s: &str| {
+            s.parse::<usize>()
+                .map_err(|e| ReadError::parse(1, format!("bad header value {}: {}", s, e)))
+        };
+        let (width, height) = (number(&fields[1])?, number(&fields[2])?);
+        if number(&fields[3])? != 255 {
+            return Err(ReadError::parse(1, "only 8-bit images are supported"));
+        }
+
+        let size = width
+            .checked_mul(height)
+            .and_then(|n| n.checked_mul(channels))
+            .ok_or_else(|| ReadError::parse(1, "image size is too large"))?;
+
+        let body = &data[(i + 1).min(data.len())..];
+        if body.len() != size {
+            return Err(ReadError::parse(0, "pixel data does not match the size"));
+        }
+        let pixels = body
+            .chunks(channels)
+            .map(|c| match *c {
+                [g] => [g, g, g],
+                [r, g, b] => [r, g, b],
+                _ => unreachable!(),
+            })
+            .collect();
+        Ok(Image {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+fn luminance([r, g, b]: [u8; 3]) -> u8 {
+    (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32).round() as u8
+}
+
+/// Renders triangles seen through a [`Camera`] into an [`Image`].
+///
+/// Every pixel is lit by a single directional light, by default shining
+/// from the camera, with the Lambert term of the normal interpolated from
+/// the vertex normals. Vertices without normals use the normal of their
+/// triangle. Triangles with a vertex behind the near plane of the camera are
+/// left out rather than clipped.
+///
+/// [`Camera`]: ../struct.Camera.html
+/// [`Image`]: struct.Image.html
+#[derive(Clone, Copy, Debug)]
+pub struct Rasterizer {
+    width: usize,
+    height: usize,
+    camera: Camera,
+    color: [f32; 3],
+    background: [f32; 3],
+    light: Option<Position>,
+    ambient: f32,
+    cull_back_faces: bool,
+}
+
+// a vertex projected onto the image
+#[derive(Clone, Copy)]
+struct Projected {
+    x: f32,
+    y: f32,
+    depth: f32,
+    normal: Vector3,
+}
+
+impl Rasterizer {
+    /// Creates a new `Rasterizer` for images of `width` by `height` pixels,
+    /// drawing white triangles on black.
+    pub fn new(width: usize, height: usize, camera: Camera) -> Self {
+        Rasterizer {
+            width,
+            height,
+            camera,
+            color: [1., 1., 1.],
+            background: [0., 0., 0.],
+            light: None,
+            ambient: 0.,
+            cull_back_faces: false,
+        }
+    }
+
+    /// Sets the color of the triangles, from 0 to 1.
+    pub fn color(self, color: [f32; 3]) -> Self {
+        Rasterizer { color, ..self }
+    }
+
+    /// Sets the color of the pixels no triangle covers, from 0 to 1.
+    pub fn background(self, background: [f32; 3]) -> Self {
+        Rasterizer { background, ..self }
+    }
+
+    /// Sets the direction that the light comes from, or `None` to light
+    /// the triangles from the camera.
+    pub fn light(self, light: Option<Position>) -> Self {
+        Rasterizer { light, ..self }
+    }
+
+    /// Sets how much of the color is seen without light, from 0 to 1.
+    pub fn ambient(self, ambient: f32) -> Self {
+        Rasterizer { ambient, ..self }
+    }
+
+    /// Sets whether triangles facing away from the camera are dropped.
+    /// Triangles face the camera when their vertices run counter-clockwise
+    /// on the screen.
+    pub fn cull_back_faces(self, cull_back_faces: bool) -> Self {
+        Rasterizer {
+            cull_back_faces,
+            ..self
+        }
+    }
+
+    /// Renders a stream of polygons, such as the output of a generator.
+    pub fn render<I, P, V>(&self, polygons: I) -> Image
+    where
+        I: IntoIterator<Item = P>,
+        P: EmitTriangles<Vertex = V>,
+        V: VertexAttributes,
+    {
+        let mut color = vec![self.background; self.width * self.height];
+        let mut depth = vec![f32::INFINITY; self.width * self.height];
+        let aspect = self.width as f32 / self.height as f32;
+        let light = self.light.map(|l| Vector3::from(l).normalized());
+
+        for p in polygons {
+            p.emit_triangles(|t| {
+                let world = [t.x.position(), t.y.position(), t.z.position()];
+                let a = Vector3::from(world[0]);
+                let face = (Vector3::from(world[1]) - a).cross(Vector3::from(world[2]) - a);
+                if face.magnitude_squared() == 0. {
+                    return;
+                }
+                let face = face.normalized();
+                let normals = [t.x.normal(), t.y.normal(), t.z.normal()];
+
+                let mut corners = [None; 3];
+                for k in 0..3 {
+                    corners[k] = self.camera.project(world[k], aspect).map(|p| Projected {
+                        x: (p.x + 1.) / 2. * self.width as f32,
+                        y: (1. - p.y) / 2. * self.height as f32,
+                        depth: p.z,
+                        normal: normals[k].map_or(face, |n| Vector3::from(n).normalized()),
+                    });
+                }
+                let corners = match corners {
+                    [Some(a), Some(b), Some(c)] => [a, b, c],
+                    _ => return,
+                };
+
+                let center = (a + Vector3::from(world[1]) + Vector3::from(world[2])) * (1. / 3.);
+                let light = light.unwrap_or_else(|| self.camera.towards_eye(center));
+                self.fill(&corners, light, &mut color, &mut depth);
+            });
+        }
+
+        Image {
+            width: self.width,
+            height: self.height,
+            pixels: color
+                .into_iter()
+                .map(|c| [channel(c[0]), channel(c[1]), channel(c[2])])
+                .collect(),
+        }
+    }
+
+    fn fill(&self, c: &[Projected; 3], light: Vector3, color: &mut [[f32; 3]], depth: &mut [f32]) {
+        // the image runs downwards, so front faces run clockwise on it
+        let area = edge(c[0], c[1], c[2].x, c[2].y);
+        if area == 0. || (self.cull_back_faces && area > 0.) {
+            return;
+        }
+
+        let min_x = c.iter().map(|p| p.x).fold(f32::INFINITY, f32::min).max(0.);
+        let max_x = c.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+        let min_y = c.iter().map(|p| p.y).fold(f32::INFINITY, f32::min).max(0.);
+        let max_y = c.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+        let max_x = (max_x.ceil() as usize).min(self.width);
+        let max_y = (max_y.ceil() as usize).min(self.height);
+        let perspective = self.camera.is_perspective();
+
+        for y in min_y as usize..max_y {
+            for x in min_x as usize..max_x {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let b = [
+                    edge(c[1], c[2], px, py) / area,
+                    edge(c[2], c[0], px, py) / area,
+                    edge(c[0], c[1], px, py) / area,
+                ];
+                if b.iter().any(|&b| b < 0.) {
+                    continue;
+                }
+
+                // screen space weights become view space weights by
+                // dividing by the depth under a perspective projection
+                let w = if perspective {
+                    let w = [b[0] / c[0].depth, b[1] / c[1].depth, b[2] / c[2].depth];
+                    let sum = w[0] + w[1] + w[2];
+                    [w[0] / sum, w[1] / sum, w[2] / sum]
+                } else {
+                    b
+                };
+                let z = w[0] * c[0].depth + w[1] * c[1].depth + w[2] * c[2].depth;
+                let i = y * self.width + x;
+                if z >= depth[i] {
+                    continue;
+                }
+                depth[i] = z;
+
+                let normal = c[0].normal * w[0] + c[1].normal * w[1] + c[2].normal * w[2];
+                let lambert = if normal.magnitude_squared() > 0. {
+                    normal.normalized().dot(light).max(0.)
+                } else {
+                    0.
+                };
+                let shade = self.ambient + (1. - self.ambient) * lambert;
+                color[i] = [
+                    self.color[0] * shade,
+                    self.color[1] * shade,
+                    self.color[2] * shade,
+                ];
+            }
+        }
+    }
+}
+
+// twice the signed area of the triangle from `a` and `b` to the point
+fn edge(a: Projected, b: Projected, x: f32, y: f32) -> f32 {
+    (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+fn channel(value: f32) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
+}
@@ -0,0 +1,479 @@
+//! Mesh simplification by edge collapse using quadric error metrics.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::math::Vector3;
+use crate::{Position, Triangle};
+
+// How much the planes that keep an unlocked boundary in place weigh
+// compared to the planes of the faces.
+const BOUNDARY_WEIGHT: f64 = 10.0;
+
+/// Reduces the triangle count of an indexed triangle mesh by collapsing
+/// edges in the order of least quadric error, as described by Garland and
+/// Heckbert.
+///
+/// Collapses always move a vertex onto one of its neighbors, so the
+/// simplified triangles index into the same vertex buffer as the source
+/// mesh. Vertices that share a position are treated as a single point of
+/// the surface, which keeps attribute seams (such as the ones `Cube` emits
+/// for its hard edges) from tearing open.
+///
+/// ```
+/// use genmesh::generators::{IcoSphere, IndexedPolygon, SharedVertex};
+/// use genmesh::{Simplifier, Vertex};
+///
+/// let sphere = IcoSphere::subdivide(3);
+/// let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+/// let triangles: Vec<_> = sphere.indexed_polygon_iter().collect();
+///
+/// let (simplified, error) = Simplifier::new(320).simplify(&vertices, &triangles, |v| v.pos);
+/// assert!(simplified.len() <= 320);
+/// assert!(error < 0.1);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Simplifier {
+    target_triangles: usize,
+    max_error: f32,
+    lock_boundary: bool,
+    preserve_seams: bool,
+}
+
+// a target of zero simplifies as far as the other options allow
+impl Default for Simplifier {
+    fn default() -> Self {
+        Simplifier::new(0)
+    }
+}
+
+impl Simplifier {
+    /// Creates a new `Simplifier` that collapses edges until at most
+    /// `target_triangles` remain. Boundaries are locked and seams may be
+    /// collapsed along with the surface by default.
+    pub fn new(target_triangles: usize) -> Self {
+        Simplifier {
+            target_triangles,
+            max_error: f32::INFINITY,
+            lock_boundary: true,
+            preserve_seams: false,
+        }
+    }
+
+    /// Sets the number of triangles to simplify down to.
+    pub fn target_triangles(self, target_triangles: usize) -> Self {
+        Simplifier {
+            target_triangles,
+            ..self
+        }
+    }
+
+    /// Stops simplifying before any collapse whose error would exceed
+    /// `max_error`. The error is measured in the units of the mesh, and
+    /// approximates the distance between the simplified and the source
+    /// surface.
+    pub fn max_error(self, max_error: f32) -> Self {
+        Simplifier { max_error, ..self }
+    }
+
+    /// Controls whether the vertices on the open boundaries of the mesh
+    /// are kept in place. Unlocked boundaries can still be collapsed
+    /// along their length, but are kept from shrinking inwards.
+    pub fn lock_boundary(self, lock_boundary: bool) -> Self {
+        Simplifier {
+            lock_boundary,
+            ..self
+        }
+    }
+
+    /// Controls whether the vertices on attribute seams, meaning
+    /// separate vertices that share one position, are kept in place.
+    pub fn preserve_seams(self, preserve_seams: bool) -> Self {
+        Simplifier {
+            preserve_seams,
+            ..self
+        }
+    }
+
+    /// Simplifies `triangles`, returning the remaining triangles along
+    /// with the largest error introduced by any of the collapses.
+    ///
+    /// You must supply a function that can be used to lookup
+    /// the position of a vertex.
+    pub fn simplify<T, F>(
+        &self,
+        vertices: &[T],
+        triangles: &[Triangle<usize>],
+        mut f: F,
+    ) -> (Vec<Triangle<usize>>, f32)
+    where
+        F: FnMut(&T) -> Position,
+    {
+        let mut mesh = Mesh::new(vertices.iter().map(&mut f).collect(), triangles);
+        if !self.lock_boundary {
+            mesh.add_boundary_quadrics();
+        }
+        let mut error = 0f64;
+
+        while mesh.alive > self.target_triangles {
+            let locked = mesh.locked_points(self.lock_boundary, self.preserve_seams);
+            let mut candidates = mesh.candidates(&locked);
+            candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut touched = vec![false; mesh.points.len()];
+            let mut collapsed = false;
+            for (cost, from, to) in candidates {
+                let cost = cost.max(0.);
+                if cost.sqrt() > self.max_error as f64 || mesh.alive <= self.target_triangles {
+                    break;
+                }
+                if touched[from] || touched[to] {
+                    continue;
+                }
+                if mesh.collapse(from, to) {
+                    touched[from] = true;
+                    touched[to] = true;
+                    error = error.max(cost);
+                    collapsed = true;
+                }
+            }
+
+            if !collapsed {
+                break;
+            }
+        }
+
+        let triangles = mesh
+            .triangles
+            .iter()
+            .zip(mesh.dead.iter())
+            .filter(|&(_, &dead)| !dead)
+            .map(|(&t, _)| t)
+            .collect();
+        (triangles, error.sqrt() as f32)
+    }
+}
+
+// A symmetric 4x4 matrix, stored as its upper triangle, along with the
+// total weight of the planes that were summed into it.
+#[derive(Clone, Copy, Debug, Default)]
+struct Quadric([f64; 10], f64);
+
+impl Quadric {
+    fn plane(n: [f64; 3], d: f64, weight: f64) -> Self {
+        let [a, b, c] = n;
+        Quadric(
+            [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|x| x * weight),
+            weight,
+        )
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a += b;
+        }
+        self.1 += other.1;
+    }
+
+    // The mean squared distance of `p` from the planes.
+    fn error(&self, p: [f64; 3]) -> f64 {
+        let [a2, ab, ac, ad, b2, bc, bd, c2, cd, d2] = self.0;
+        let [x, y, z] = p;
+        if self.1 == 0. {
+            return 0.;
+        }
+        (x * x * a2
+            + 2. * x * y * ab
+            + 2. * x * z * ac
+            + 2. * x * ad
+            + y * y * b2
+            + 2. * y * z * bc
+            + 2. * y * bd
+            + z * z * c2
+            + 2. * z * cd
+            + d2)
+            / self.1
+    }
+}
+
+fn to_f64(p: Vector3) -> [f64; 3] {
+    let [x, y, z]: [f32; 3] = p.into();
+    [x as f64, y as f64, z as f64]
+}
+
+// Returns the unit normal and the offset of the plane through `a`, `b`
+// and `c`, or `None` if the triangle is degenerate.
+fn plane_through(a: Vector3, b: Vector3, c: Vector3) -> Option<(Vector3, f64)> {
+    let n = (b - a).cross(c - a);
+    if n.magnitude_squared() == 0. {
+        return None;
+    }
+    let n = n.normalized();
+    Some((n, -(n.dot(a) as f64)))
+}
+
+// The working state of the simplifier. Vertices that share a position are
+// welded into a single point, the collapses work on those points.
+struct Mesh {
+    // position of every point
+    points: Vec<Vector3>,
+    // the point of every vertex
+    point_of: Vec<usize>,
+    // the vertices of every point, more than one means a seam
+    copies: Vec<Vec<usize>>,
+    // triangles that reference every point, may contain dead triangles
+    point_triangles: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    triangles: Vec<Triangle<usize>>,
+    dead: Vec<bool>,
+    alive: usize,
+}
+
+impl Mesh {
+    fn new(positions: Vec<Position>, triangles: &[Triangle<usize>]) -> Self {
+        let mut lookup = HashMap::new();
+        let mut points = Vec::new();
+        let mut copies = Vec::new();
+        let point_of: Vec<usize> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                // adding zero folds -0.0 into 0.0
+                let key = [p.x + 0., p.y + 0., p.z + 0.].map(f32::to_bits);
+                let point = *lookup.entry(key).or_insert_with(|| {
+                    points.push(Vector3::from(*p));
+                    copies.push(Vec::new());
+                    points.len() - 1
+                });
+                copies[point].push(i);
+                point
+            })
+            .collect();
+
+        let mut point_triangles = vec![Vec::new(); points.len()];
+        let mut quadrics = vec![Quadric::default(); points.len()];
+        let mut dead = vec![false; triangles.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            let [a, b, c] = [t.x, t.y, t.z].map(|v| point_of[v]);
+            if a == b || b == c || c == a {
+                dead[i] = true;
+                continue;
+            }
+            for &p in &[a, b, c] {
+                point_triangles[p].push(i);
+            }
+            if let Some((n, d)) = plane_through(points[a], points[b], points[c]) {
+                let q = Quadric::plane(to_f64(n), d, 1.);
+                for &p in &[a, b, c] {
+                    quadrics[p].add(&q);
+                }
+            }
+        }
+
+        let alive = dead.iter().filter(|&&d| !d).count();
+        Mesh {
+            points,
+            point_of,
+            copies,
+            point_triangles,
+            quadrics,
+            triangles: triangles.to_vec(),
+            dead,
+            alive,
+        }
+    }
+
+    fn corners(&self, t: usize) -> [usize; 3] {
+        let Triangle { x, y, z } = self.triangles[t];
+        [x, y, z].map(|v| self.point_of[v])
+    }
+
+    fn alive_triangles(&self, p: usize) -> impl Iterator<Item = usize> + '_ {
+        self.point_triangles[p]
+            .iter()
+            .copied()
+            .filter(move |&t| !self.dead[t])
+    }
+
+    // Counts the triangles on each edge, keyed by the sorted point pair.
+    fn edges(&self) -> HashMap<(usize, usize), usize> {
+        let mut edges = HashMap::new();
+        for t in (0..self.triangles.len()).filter(|&t| !self.dead[t]) {
+            let [a, b, c] = self.corners(t);
+            for &(u, v) in &[(a, b), (b, c), (c, a)] {
+                *edges.entry((u.min(v), u.max(v))).or_insert(0) += 1;
+            }
+        }
+        edges
+    }
+
+    fn locked_points(&self, lock_boundary: bool, preserve_seams: bool) -> Vec<bool> {
+        let mut locked: Vec<bool> = self
+            .copies
+            .iter()
+            .map(|c| preserve_seams && c.len() > 1)
+            .collect();
+        for ((a, b), count) in self.edges() {
+            // non-manifold edges are never collapsed safely
+            if (lock_boundary && count == 1) || count > 2 {
+                locked[a] = true;
+                locked[b] = true;
+            }
+        }
+        locked
+    }
+
+    // Keeps the open boundaries in place with planes perpendicular to them.
+    fn add_boundary_quadrics(&mut self) {
+        // keep the boundary in place with planes perpendicular to it
+        for (&(a, b), _) in self.edges().iter().filter(|&(_, &count)| count == 1) {
+            let t = self
+                .alive_triangles(a)
+                .find(|&t| self.corners(t).contains(&b))
+                .unwrap();
+            let [x, y, z] = self.corners(t);
+            if let Some((n, _)) = plane_through(self.points[x], self.points[y], self.points[z]) {
+                let e = self.points[b] - self.points[a];
+                if e.magnitude_squared() > 0. {
+                    let bn = e.cross(n).normalized();
+                    let d = -(bn.dot(self.points[a]) as f64);
+                    let q = Quadric::plane(to_f64(bn), d, BOUNDARY_WEIGHT);
+                    self.quadrics[a].add(&q);
+                    self.quadrics[b].add(&q);
+                }
+            }
+        }
+    }
+
+    // Lists every possible collapse as `(cost, from, to)`, picking the
+    // cheaper direction for every edge and skipping non-finite costs.
+    fn candidates(&self, locked: &[bool]) -> Vec<(f64, usize, usize)> {
+        let edges = self.edges();
+        let mut candidates = Vec::new();
+        for &(a, b) in edges.keys() {
+            let mut q = self.quadrics[a];
+            q.add(&self.quadrics[b]);
+            let options = [(a, b), (b, a)]
+                .iter()
+                .filter(|&&(from, _)| !locked[from])
+                .map(|&(from, to)| (q.error(to_f64(self.points[to])), from, to))
+                // non-finite positions give costs that can not be compared
+                .filter(|&(cost, _, _)| cost.is_finite())
+                .min_by(|x, y| x.0.total_cmp(&y.0));
+            candidates.extend(options);
+        }
+        candidates
+    }
+
+    // Collapses point `from` onto point `to`, unless doing so would fold
+    // the surface over or change its topology.
+    fn collapse(&mut self, from: usize, to: usize) -> bool {
+        // link condition: the only neighbors the two points may share are
+        // the ones across the triangles on the collapsing edge
+        let neighbors = |p: usize| -> HashSet<usize> {
+            self.alive_triangles(p)
+                .flat_map(|t| self.corners(t))
+                .filter(|&q| q != p)
+                .collect()
+        };
+        let shared = neighbors(from)
+            .intersection(&neighbors(to))
+            .copied()
+            .collect::<HashSet<_>>();
+        let across: HashSet<usize> = self
+            .alive_triangles(from)
+            .map(|t| self.corners(t))
+            .filter(|c| c.contains(&to))
+            .flatten()
+            .filter(|&q| q != from && q != to)
+            .collect();
+        if shared != across || across.is_empty() {
+            return false;
+        }
+
+        // every copy of `from` has to move onto the copy of `to` that it
+        // shares a triangle with
+        let mut target = HashMap::new();
+        for &v in &self.copies[from] {
+            let mut found = None;
+            for t in self.point_triangles[from].iter().copied() {
+                let Triangle { x, y, z } = self.triangles[t];
+                if self.dead[t] || ![x, y, z].contains(&v) {
+                    continue;
+                }
+                for &w in &[x, y, z] {
+                    if self.point_of[w] == to {
+                        if found.is_some_and(|f| f != w) {
+                            return false;
+                        }
+                        found = Some(w);
+                    }
+                }
+            }
+            match found {
+                Some(w) => {
+                    target.insert(v, w);
+                }
+                // the copy does not touch the edge, yet still has triangles
+                None if self.alive_triangles(from).any(|t| {
+                    let Triangle { x, y, z } = self.triangles[t];
+                    [x, y, z].contains(&v)
+                }) =>
+                {
+                    return false
+                }
+                None => {}
+            }
+        }
+
+        // reject collapses that flip any of the remaining triangles
+        for t in self.alive_triangles(from) {
+            let c = self.corners(t);
+            if c.contains(&to) {
+                continue;
+            }
+            let before = c.map(|p| self.points[p]);
+            let after = c.map(|p| {
+                if p == from {
+                    self.points[to]
+                } else {
+                    self.points[p]
+                }
+            });
+            let n0 = (before[1] - before[0]).cross(before[2] - before[0]);
+            let n1 = (after[1] - after[0]).cross(after[2] - after[0]);
+            if n0.dot(n1) <= 0. {
+                return false;
+            }
+        }
+
+        let moved: Vec<usize> = self.alive_triangles(from).collect();
+        for t in moved {
+            if self.corners(t).contains(&to) {
+                self.dead[t] = true;
+                self.alive -= 1;
+                continue;
+            }
+            let Triangle { x, y, z } = self.triangles[t];
+            let remap = |v: usize| *target.get(&v).unwrap_or(&v);
+            self.triangles[t] = Triangle::new(remap(x), remap(y), remap(z));
+            self.point_triangles[to].push(t);
+        }
+        self.point_triangles[from].clear();
+
+        let q = self.quadrics[from];
+        self.quadrics[to].add(&q);
+        true
+    }
+}
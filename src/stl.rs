@@ -0,0 +1,205 @@
+//! Reading and writing STL files, in both their ASCII and binary forms.
+//!
+//! STL stores unconnected triangles, each with a facet normal. Writers
+//! triangulate polygons on the fly and compute the facet normals from the
+//! positions, while readers drop the facet normals.
+//!
+//! ```
+//! use genmesh::generators::Torus;
+//! use genmesh::stl;
+//!
+//! let mut binary = Vec::new();
+//! stl::write_binary(&mut binary, Torus::new(1., 0.25, 16, 8)).unwrap();
+//! assert_eq!(binary.len(), 84 + 50 * 16 * 8 * 2);
+//!
+//! let triangles = stl::read(&binary[..]).unwrap();
+//! assert_eq!(triangles.len(), 16 * 8 * 2);
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::math::Vector3;
+use crate::{EmitTriangles, Indexer, MapVertex, Position, ReadError, Triangle, VertexAttributes};
+
+const HEADER: &[u8] = b"binary STL written by genmesh";
+
+/// Writes polygons to an ASCII STL file, as a solid called `name`.
+pub fn write_ascii<W, I, P, V>(mut out: W, name: &str, polygons: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = P>,
+    P: EmitTriangles<Vertex = V>,
+    V: VertexAttributes,
+{
+    writeln!(out, "solid {}", name)?;
+    for t in triangles(polygons) {
+        let n = facet_normal(&t);
+        writeln!(out, "  facet normal {} {} {}", n.x, n.y, n.z)?;
+        writeln!(out, "    outer loop")?;
+        for p in &[t.x, t.y, t.z] {
+            writeln!(out, "      vertex {} {} {}", p.x, p.y, p.z)?;
+        }
+        writeln!(out, "    endloop")?;
+        writeln!(out, "  endfacet")?;
+    }
+    writeln!(out, "endsolid {}", name)
+}
+
+/// Writes polygons to a binary STL file.
+pub fn write_binary<W, I, P, V>(mut out: W, polygons: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = P>,
+    P: EmitTriangles<Vertex = V>,
+    V: VertexAttributes,
+{
+    let triangles = triangles(polygons);
+    let mut header = [0u8; 80];
+    header[..HEADER.len()].copy_from_slice(HEADER);
+    out.write_all(&header)?;
+    out.write_all(&(triangles.len() as u32).to_le_bytes())?;
+
+    for t in &triangles {
+        let n = facet_normal(t);
+        for p in &[n, t.x, t.y, t.z] {
+            for c in &[p.x, p.y, p.z] {
+                out.write_all(&c.to_le_bytes())?;
+            }
+        }
+        // the attribute byte count, which is unused
+        out.write_all(&[0, 0])?;
+    }
+    Ok(())
+}
+
+/// Reads the triangles of an STL file, telling ASCII and binary files
+/// apart by their contents.
+pub fn read<R: Read>(mut input: R) -> Result<Vec<Triangle<Position>>, ReadError> {
+    let mut data = Vec::new();
+    input.read_to_end(&mut data)?;
+
+    if data.len() >= 84 {
+        let count = u32::from_le_bytes([data[80], data[81], data[82], data[83]]) as usize;
+        if data.len() as u64 == 84 + 50 * count as u64 {
+            return Ok(read_binary(&data[84..], count));
+        }
+    }
+    match std::str::from_utf8(&data) {
+        Ok(text) if text.trim_start().starts_with("solid") => read_ascii(text),
+        _ => Err(ReadError::parse(
+            0,
+            "binary STL size does not match its triangle count",
+        )),
+    }
+}
+
+/// Reads an STL file into an indexed mesh, welding the corners of the
+/// triangles with `indexer`.
+///
+/// ```
+/// use genmesh::generators::IcoSphere;
+/// use genmesh::{stl, LruIndexer};
+///
+/// let mut ascii = Vec::new();
+/// stl::write_ascii(&mut ascii, "sphere", IcoSphere::new()).unwrap();
+///
+/// let mut positions = Vec::new();
+/// let mut indexer = LruIndexer::new(16, |_, p| positions.push(p));
+/// let triangles = stl::read_indexed(&ascii[..], &mut indexer).unwrap();
+/// assert_eq!(triangles.len(), 20);
+/// drop(indexer);
+/// assert_eq!(positions.len(), 12);
+/// ```
+pub fn read_indexed<R, I>(input: R, indexer: &mut I) -> Result<Vec<Triangle<usize>>, ReadError>
+where
+    R: Read,
+    I: Indexer<Position>,
+{
+    Ok(read(input)?
+        .into_iter()
+        .map(|t| t.map_vertex(|p| indexer.index(p)))
+        .collect())
+}
+
+fn triangles<I, P, V>(polygons: I) -> Vec<Triangle<Position>>
+where
+    I: IntoIterator<Item = P>,
+    P: EmitTriangles<Vertex = V>,
+    V: VertexAttributes,
+{
+    let mut triangles = Vec::new();
+    for p in polygons {
+        p.emit_triangles(|t| {
+            triangles.push(Triangle::new(
+                t.x.position(),
+                t.y.position(),
+                t.z.position(),
+            ))
+        });
+    }
+    triangles
+}
+
+fn facet_normal(t: &Triangle<Position>) -> Position {
+    let [a, b, c] = [t.x, t.y, t.z].map(Vector3::from);
+    let n = (b - a).cross(c - a);
+    if n.magnitude_squared() > 0. {
+        n.normalized().into()
+    } else {
+        n.into()
+    }
+}
+
+fn read_binary(data: &[u8], count: usize) -> Vec<Triangle<Position>> {
+    let float =
+        |at: usize| f32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+    let point = |at: usize| Position::from([float(at), float(at + 4), float(at + 8)]);
+    (0..count)
+        .map(|i| {
+            // skip the facet normal
+            let at = i * 50 + 12;
+            Triangle::new(point(at), point(at + 12), point(at + 24))
+        })
+        .collect()
+}
+
+fn read_ascii(text: &str) -> Result<Vec<Triangle<Position>>, ReadError> {
+    let mut triangles = Vec::new();
+    let mut corners: Vec<Position> = Vec::with_capacity(3);
+    let mut in_loop = false;
+
+    for (number, line) in text.lines().enumerate() {
+        let number = number + 1;
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("vertex") if in_loop => {
+                let values = words
+                    .map(|w| w.parse::<f32>())
+                    .collect::<Result<Vec<f32>, _>>()
+                    .map_err(|e| ReadError::parse(number, format!("vertex: {}", e)))?;
+                if values.len() != 3 {
+                    return Err(ReadError::parse(number, "vertex needs 3 values"));
+                }
+                corners.push([values[0], values[1], values[2]].into());
+            }
+            Some("outer") => {
+                in_loop = true;
+                corners.clear();
+            }
+            Some("endloop") => {
+                if corners.len() != 3 {
+                    return Err(ReadError::parse(number, "facet needs 3 vertices"));
+                }
+                triangles.push(Triangle::new(corners[0], corners[1], corners[2]));
+                in_loop = false;
+            }
+            Some("vertex") => return Err(ReadError::parse(number, "vertex outside a loop")),
+            Some("solid") | Some("facet") | Some("endfacet") | Some("endsolid") | None => {}
+            Some(word) => return Err(ReadError::parse(number, format!("unexpected {}", word))),
+        }
+    }
+    if in_loop {
+        return Err(ReadError::parse(text.lines().count(), "unterminated loop"));
+    }
+    Ok(triangles)
+}
@@ -0,0 +1,451 @@
+//! Subdivision schemes for indexed meshes.
+//!
+//! Every scheme creates its new vertices through a user supplied `blend`
+//! function, which receives the source vertices along with their weights
+//! and returns their weighted combination. The weights always sum up to
+//! one. This lets custom vertex types carry any attribute through the
+//! subdivision.
+//!
+//! ```
+//! use genmesh::generators::{IcoSphere, IndexedPolygon, SharedVertex};
+//! use genmesh::{loop_subdivide, Vertex};
+//!
+//! fn blend(weights: &[(&Vertex, f32)]) -> Vertex {
+//!     let mut out = Vertex {
+//!         pos: [0., 0., 0.].into(),
+//!         normal: [0., 0., 0.].into(),
+//!     };
+//!     for &(v, w) in weights {
+//!         out.pos.x += v.pos.x * w;
+//!         out.pos.y += v.pos.y * w;
+//!         out.pos.z += v.pos.z * w;
+//!     }
+//!     out
+//! }
+//!
+//! let sphere = IcoSphere::new();
+//! let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+//! let triangles: Vec<_> = sphere.indexed_polygon_iter().collect();
+//! let (vertices, triangles) = loop_subdivide(&vertices, &triangles, blend);
+//! assert_eq!((vertices.len(), triangles.len()), (42, 80));
+//! ```
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use crate::{EmitVertices, Line, Polygon, Quad, Triangle};
+
+/// Sharpness of the tagged edges of a mesh, for [`catmull_clark`].
+///
+/// [`catmull_clark`]: fn.catmull_clark.html
+pub type Creases = HashMap<Line<usize>, f32>;
+
+// Sorted vertex pair, so that both directions of an edge share a key.
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Subdivides a triangle mesh with Charles Loop's scheme, splitting every
+/// triangle into four and smoothing the result. Open boundaries are
+/// smoothed as curves of their own, and non-manifold vertices are left in
+/// place.
+///
+/// The source vertices keep their indices, with the vertices created on
+/// the edges following after them. Connectivity is taken from the indices
+/// alone, so vertices that share a position but not an index form a
+/// boundary.
+pub fn loop_subdivide<T, F>(
+    vertices: &[T],
+    triangles: &[Triangle<usize>],
+    mut blend: F,
+) -> (Vec<T>, Vec<Triangle<usize>>)
+where
+    F: FnMut(&[(&T, f32)]) -> T,
+{
+    // the opposite corners of the triangles on each edge
+    let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for &Triangle { x, y, z } in triangles {
+        for &(a, b, c) in &[(x, y, z), (y, z, x), (z, x, y)] {
+            edges.entry(edge_key(a, b)).or_default().push(c);
+        }
+    }
+
+    let mut neighbors = vec![Vec::new(); vertices.len()];
+    let mut boundary = vec![Vec::new(); vertices.len()];
+    let mut non_manifold = vec![false; vertices.len()];
+    for (&(a, b), opposite) in &edges {
+        neighbors[a].push(b);
+        neighbors[b].push(a);
+        match opposite.len() {
+            1 => {
+                boundary[a].push(b);
+                boundary[b].push(a);
+            }
+            2 => {}
+            _ => {
+                non_manifold[a] = true;
+                non_manifold[b] = true;
+            }
+        }
+    }
+
+    let mut out = Vec::with_capacity(vertices.len() + edges.len());
+    for (i, v) in vertices.iter().enumerate() {
+        let weights: Vec<(&T, f32)> = if non_manifold[i] || neighbors[i].is_empty() {
+            vec![(v, 1.)]
+        } else if !boundary[i].is_empty() {
+            if boundary[i].len() == 2 {
+                vec![
+                    (v, 0.75),
+                    (&vertices[boundary[i][0]], 0.125),
+                    (&vertices[boundary[i][1]], 0.125),
+                ]
+            } else {
+                vec![(v, 1.)]
+            }
+        } else {
+            let n = neighbors[i].len() as f32;
+            let c = 0.375 + 0.25 * (2. * PI / n).cos();
+            let beta = (0.625 - c * c) / n;
+            let mut weights = vec![(v, 1. - n * beta)];
+            weights.extend(neighbors[i].iter().map(|&j| (&vertices[j], beta)));
+            weights
+        };
+        out.push(blend(&weights));
+    }
+
+    let mut edge_vertex = HashMap::with_capacity(edges.len());
+    let mut keys: Vec<_> = edges.keys().copied().collect();
+    keys.sort();
+    for (a, b) in keys {
+        let opposite = &edges[&(a, b)];
+        let weights = if opposite.len() == 2 {
+            vec![
+                (&vertices[a], 0.375),
+                (&vertices[b], 0.375),
+                (&vertices[opposite[0]], 0.125),
+                (&vertices[opposite[1]], 0.125),
+            ]
+        } else {
+            vec![(&vertices[a], 0.5), (&vertices[b], 0.5)]
+        };
+        edge_vertex.insert((a, b), out.len());
+        out.push(blend(&weights));
+    }
+
+    let mut faces = Vec::with_capacity(triangles.len() * 4);
+    for &Triangle { x, y, z } in triangles {
+        let xy = edge_vertex[&edge_key(x, y)];
+        let yz = edge_vertex[&edge_key(y, z)];
+        let zx = edge_vertex[&edge_key(z, x)];
+        faces.push(Triangle::new(x, xy, zx));
+        faces.push(Triangle::new(y, yz, xy));
+        faces.push(Triangle::new(z, zx, yz));
+        faces.push(Triangle::new(xy, yz, zx));
+    }
+
+    (out, faces)
+}
+
+/// Subdivides a mesh of arbitrary polygons with the Catmull-Clark scheme,
+/// splitting every n-sided polygon into n quads and smoothing the result.
+///
+/// Edges can be tagged with a sharpness in `creases`, in either direction.
+/// An edge with a sharpness of `1` or more is kept sharp through this
+/// subdivision, a fraction blends between a sharp and a smooth edge, and
+/// open boundaries are always sharp. The creases of the output mesh are
+/// returned alongside it with their sharpness reduced by one, ready for
+/// the next subdivision, so semi-sharp creases soften gradually.
+///
+/// The source vertices keep their indices, followed by one vertex per
+/// polygon and then one vertex per edge. Connectivity is taken from the
+/// indices alone, so vertices that share a position but not an index form
+/// a boundary.
+pub fn catmull_clark<T, P, F>(
+    vertices: &[T],
+    polygons: &[P],
+    creases: &Creases,
+    mut blend: F,
+) -> (Vec<T>, Vec<Quad<usize>>, Creases)
+where
+    P: EmitVertices<usize> + Clone,
+    F: FnMut(&[(&T, f32)]) -> T,
+{
+    let faces: Vec<Vec<usize>> = polygons
+        .iter()
+        .map(|p| {
+            let mut face = Vec::with_capacity(4);
+            p.clone().emit_vertices(|v| face.push(v));
+            face
+        })
+        .collect();
+
+    let sharpness = |a: usize, b: usize| -> f32 {
+        let s = creases
+            .get(&Line::new(a, b))
+            .or_else(|| creases.get(&Line::new(b, a)));
+        s.copied().unwrap_or(0.).max(0.)
+    };
+
+    // the faces on each edge
+    let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (i, face) in faces.iter().enumerate() {
+        for (j, &a) in face.iter().enumerate() {
+            let b = face[(j + 1) % face.len()];
+            edges.entry(edge_key(a, b)).or_default().push(i);
+        }
+    }
+    let mut keys: Vec<_> = edges.keys().copied().collect();
+    keys.sort();
+
+    // boundaries and non-manifold edges are infinitely sharp
+    let edge_sharpness: HashMap<(usize, usize), f32> = keys
+        .iter()
+        .map(|&(a, b)| {
+            let s = if edges[&(a, b)].len() == 2 {
+                sharpness(a, b)
+            } else {
+                f32::INFINITY
+            };
+            ((a, b), s)
+        })
+        .collect();
+
+    let face_weights = |face: &[usize]| -> Vec<(usize, f32)> {
+        let w = 1. / face.len() as f32;
+        face.iter().map(|&v| (v, w)).collect()
+    };
+
+    let mut vertex_edges = vec![Vec::new(); vertices.len()];
+    let mut vertex_faces = vec![Vec::new(); vertices.len()];
+    for &(a, b) in &keys {
+        vertex_edges[a].push(b);
+        vertex_edges[b].push(a);
+    }
+    for (i, face) in faces.iter().enumerate() {
+        for &v in face {
+            vertex_faces[v].push(i);
+        }
+    }
+
+    let mut out = Vec::with_capacity(vertices.len() + faces.len() + keys.len());
+    for i in 0..vertices.len() {
+        let n = vertex_edges[i].len();
+        let sharp: Vec<(usize, f32)> = vertex_edges[i]
+            .iter()
+            .map(|&j| (j, edge_sharpness[&edge_key(i, j)]))
+            .filter(|&(_, s)| s > 0.)
+            .collect();
+
+        let smooth = || -> Vec<(usize, f32)> {
+            // (Q + 2R + (n - 3)S) / n, with Q the average of the face
+            // points and R the average of the edge midpoints
+            let n = n as f32;
+            let mut weights = vec![(i, (n - 3.) / n)];
+            let q = 1. / (n * vertex_faces[i].len() as f32);
+            for &f in &vertex_faces[i] {
+                weights.extend(face_weights(&faces[f]).iter().map(|&(v, w)| (v, w * q)));
+            }
+            for &j in &vertex_edges[i] {
+                weights.push((i, 1. / (n * n)));
+                weights.push((j, 1. / (n * n)));
+            }
+            weights
+        };
+
+        let weights = if n == 0 || sharp.len() > 2 || vertex_faces[i].len() > n {
+            vec![(i, 1.)]
+        } else if sharp.len() < 2 {
+            smooth()
+        } else {
+            let crease = vec![(i, 0.75), (sharp[0].0, 0.125), (sharp[1].0, 0.125)];
+            let s = (sharp[0].1 + sharp[1].1) * 0.5;
+            if s >= 1. {
+                crease
+            } else {
+                lerp(smooth(), crease, s)
+            }
+        };
+        out.push(blend(&resolve(vertices, &weights)));
+    }
+
+    let face_base = out.len();
+    for face in &faces {
+        out.push(blend(&resolve(vertices, &face_weights(face))));
+    }
+
+    let edge_base = out.len();
+    let mut edge_vertex = HashMap::with_capacity(keys.len());
+    for (k, &(a, b)) in keys.iter().enumerate() {
+        let sharp = vec![(a, 0.5), (b, 0.5)];
+        let s = edge_sharpness[&(a, b)];
+        let weights = if s >= 1. {
+            sharp
+        } else {
+            let mut smooth = vec![(a, 0.25), (b, 0.25)];
+            for &f in &edges[&(a, b)] {
+                let face = face_weights(&faces[f]);
+                smooth.extend(face.iter().map(|&(v, w)| (v, w * 0.25)));
+            }
+            lerp(smooth, sharp, s)
+        };
+        edge_vertex.insert((a, b), edge_base + k);
+        out.push(blend(&resolve(vertices, &weights)));
+    }
+
+    let mut quads = Vec::new();
+    for (i, face) in faces.iter().enumerate() {
+        let n = face.len();
+        for j in 0..n {
+            let next = edge_vertex[&edge_key(face[j], face[(j + 1) % n])];
+            let prev = edge_vertex[&edge_key(face[(j + n - 1) % n], face[j])];
+            quads.push(Quad::new(face[j], next, face_base + i, prev));
+        }
+    }
+
+    let mut child_creases = Creases::new();
+    for &(a, b) in &keys {
+        let s = sharpness(a, b) - 1.;
+        if s > 0. && edges[&(a, b)].len() == 2 {
+            let e = edge_vertex[&(a, b)];
+            child_creases.insert(Line::new(a, e), s);
+            child_creases.insert(Line::new(e, b), s);
+        }
+    }
+
+    (out, quads, child_creases)
+}
+
+// Blends the weights of two stencils, `t` of the way from `a` to `b`.
+fn lerp(a: Vec<(usize, f32)>, b: Vec<(usize, f32)>, t: f32) -> Vec<(usize, f32)> {
+    let a = a.into_iter().map(|(v, w)| (v, w * (1. - t)));
+    let b = b.into_iter().map(|(v, w)| (v, w * t));
+    a.chain(b).filter(|&(_, w)| w != 0.).collect()
+}
+
+fn resolve<'a, T>(vertices: &'a [T], weights: &[(usize, f32)]) -> Vec<(&'a T, f32)> {
+    weights.iter().map(|&(v, w)| (&vertices[v], w)).collect()
+}
+
+/// Splits a polygon into four polygons of the same kind at the midpoints of
+/// its edges. This is the building block of [`midpoint_subdivide`].
+///
+/// [`midpoint_subdivide`]: fn.midpoint_subdivide.html
+pub trait EmitSplit: Sized {
+    /// Splits the polygon, calling `emit` with each of the four parts.
+    ///
+    /// `midpoint` must return the index of the vertex in the middle of the
+    /// given corners. It is called with the two ends of each edge, and with
+    /// all four corners for the center of a quad.
+    fn emit_split<M, F>(self, midpoint: M, emit: F)
+    where
+        M: FnMut(&[usize]) -> usize,
+        F: FnMut(Self);
+}
+
+impl EmitSplit for Triangle<usize> {
+    fn emit_split<M, F>(self, mut midpoint: M, mut emit: F)
+    where
+        M: FnMut(&[usize]) -> usize,
+        F: FnMut(Self),
+    {
+        let Triangle { x, y, z } = self;
+        let xy = midpoint(&[x, y]);
+        let yz = midpoint(&[y, z]);
+        let zx = midpoint(&[z, x]);
+        emit(Triangle::new(x, xy, zx));
+        emit(Triangle::new(y, yz, xy));
+        emit(Triangle::new(z, zx, yz));
+        emit(Triangle::new(xy, yz, zx));
+    }
+}
+
+impl EmitSplit for Quad<usize> {
+    fn emit_split<M, F>(self, mut midpoint: M, mut emit: F)
+    where
+        M: FnMut(&[usize]) -> usize,
+        F: FnMut(Self),
+    {
+        let Quad { x, y, z, w } = self;
+        let xy = midpoint(&[x, y]);
+        let yz = midpoint(&[y, z]);
+        let zw = midpoint(&[z, w]);
+        let wx = midpoint(&[w, x]);
+        let c = midpoint(&[x, y, z, w]);
+        emit(Quad::new(x, xy, c, wx));
+        emit(Quad::new(y, yz, c, xy));
+        emit(Quad::new(z, zw, c, yz));
+        emit(Quad::new(w, wx, c, zw));
+    }
+}
+
+impl EmitSplit for Polygon<usize> {
+    fn emit_split<M, F>(self, midpoint: M, mut emit: F)
+    where
+        M: FnMut(&[usize]) -> usize,
+        F: FnMut(Self),
+    {
+        match self {
+            Polygon::PolyTri(t) => t.emit_split(midpoint, |t| emit(Polygon::PolyTri(t))),
+            Polygon::PolyQuad(q) => q.emit_split(midpoint, |q| emit(Polygon::PolyQuad(q))),
+        }
+    }
+}
+
+/// Splits every triangle into four triangles, and every quad into four
+/// quads, by inserting new vertices on the midpoints of the edges and in
+/// the centers of the quads. Unlike the smooth schemes the source vertices
+/// stay in place, which makes this useful to refine a mesh before
+/// displacing it.
+///
+/// The new vertices are passed through `project`, if one is given, which
+/// can move them onto the intended surface, such as a sphere or a signed
+/// distance field. Each edge midpoint is created once and shared by both
+/// polygons on the edge, so a watertight mesh stays watertight.
+///
+/// The source vertices keep their indices, with the new vertices following
+/// after them in the order in which the polygons create them.
+pub fn midpoint_subdivide<T, P, F>(
+    vertices: &[T],
+    polygons: &[P],
+    mut blend: F,
+    mut project: Option<&mut dyn FnMut(T) -> T>,
+) -> (Vec<T>, Vec<P>)
+where
+    T: Clone,
+    P: EmitSplit + Clone,
+    F: FnMut(&[(&T, f32)]) -> T,
+{
+    let mut out = vertices.to_vec();
+    let mut edges = HashMap::new();
+    let mut split = Vec::with_capacity(polygons.len() * 4);
+
+    for p in polygons {
+        p.clone().emit_split(
+            |corners| {
+                let key = match *corners {
+                    [a, b] => Some(edge_key(a, b)),
+                    _ => None,
+                };
+                if let Some(&i) = key.and_then(|key| edges.get(&key)) {
+                    return i;
+                }
+
+                let w = 1. / corners.len() as f32;
+                let weights: Vec<_> = corners.iter().map(|&v| (v, w)).collect();
+                let v = blend(&resolve(vertices, &weights));
+                out.push(match project.as_mut() {
+                    Some(project) => project(v),
+                    None => v,
+                });
+                if let Some(key) = key {
+                    edges.insert(key, out.len() - 1);
+                }
+                out.len() - 1
+            },
+            |p| split.push(p),
+        );
+    }
+
+    (out, split)
+}
@@ -0,0 +1,313 @@
+//! Rendering meshes to SVG images, without a GPU.
+//!
+//! The output only depends on the input and the settings of the renderer,
+//! with coordinates rounded to two decimals, so it can be compared
+//! against stored images in tests.
+//!
+//! ```
+//! use genmesh::generators::{Cube, IndexedPolygon, SharedVertex};
+//! use genmesh::svg::SvgRenderer;
+//! use genmesh::{Camera, Lines};
+//!
+//! let camera = Camera::new([3., -4., 2.].into(), [0., 0., 0.].into());
+//! let renderer = SvgRenderer::new(200, 200, camera);
+//!
+//! let mut wireframe = Vec::new();
+//! renderer.write_lines(&mut wireframe, Cube::new().lines()).unwrap();
+//!
+//! let cube = Cube::new();
+//! let vertices: Vec<_> = cube.shared_vertex_iter().collect();
+//! let quads: Vec<_> = cube.indexed_polygon_iter().collect();
+//! let mut shaded = Vec::new();
+//! renderer
+//!     .cull_back_faces(true)
+//!     .fill(Some([0.8, 0.4, 0.2]))
+//!     .write_indexed(&mut shaded, &vertices, &quads)
+//!     .unwrap();
+//! ```
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use crate::math::Vector3;
+use crate::{Camera, EmitVertices, Line, Position, VertexAttributes};
+
+/// Renders meshes seen through a [`Camera`] into SVG images.
+///
+/// Edges are drawn as lines, and polygons as flat shaded shapes drawn from
+/// back to front. Anything with a point behind the near plane of the camera
+/// is left out rather than clipped.
+///
+/// [`Camera`]: ../struct.Camera.html
+#[derive(Clone, Debug)]
+pub struct SvgRenderer {
+    width: u32,
+    height: u32,
+    camera: Camera,
+    cull_back_faces: bool,
+    fill: Option<[f32; 3]>,
+    stroke: Option<String>,
+    stroke_width: f32,
+}
+
+// a polygon projected onto the image
+struct Shape {
+    points: Vec<(f32, f32)>,
+    depth: f32,
+    light: f32,
+}
+
+impl SvgRenderer {
+    /// Creates a new `SvgRenderer` for images of `width` by `height`
+    /// pixels, drawing black wireframes of every edge.
+    pub fn new(width: u32, height: u32, camera: Camera) -> Self {
+        SvgRenderer {
+            width,
+            height,
+            camera,
+            cull_back_faces: false,
+            fill: None,
+            stroke: Some("black".to_owned()),
+            stroke_width: 1.,
+        }
+    }
+
+    /// Sets whether polygons facing away from the camera are dropped,
+    /// along with the edges only they have. Polygons face the camera when
+    /// their vertices run counter-clockwise on the screen.
+    pub fn cull_back_faces(self, cull_back_faces: bool) -> Self {
+        SvgRenderer {
+            cull_back_faces,
+            ..self
+        }
+    }
+
+    /// Sets the color that polygons are filled with, from 0 to 1, shaded
+    /// by a light at the camera. Polygons are only filled when it is set.
+    pub fn fill(self, fill: Option<[f32; 3]>) -> Self {
+        SvgRenderer { fill, ..self }
+    }
+
+    /// Sets the SVG color of the edges, or `None` to not draw them.
+    pub fn stroke(self, stroke: Option<&str>) -> Self {
+        SvgRenderer {
+            stroke: stroke.map(str::to_owned),
+            ..self
+        }
+    }
+
+    /// Sets the width of the edges in pixels.
+    pub fn stroke_width(self, stroke_width: f32) -> Self {
+        SvgRenderer {
+            stroke_width,
+            ..self
+        }
+    }
+
+    /// Renders a stream of lines, such as the output of [`Lines::lines`].
+    ///
+    /// Lines carry no faces, so back faces are not culled and nothing is
+    /// filled.
+    ///
+    /// [`Lines::lines`]: ../trait.Lines.html#tymethod.lines
+    pub fn write_lines<W, I, V>(&self, mut out: W, lines: I) -> io::Result<()>
+    where
+        W: Write,
+        I: IntoIterator<Item = Line<V>>,
+        V: VertexAttributes,
+    {
+        self.write_header(&mut out)?;
+        if let Some(stroke) = &self.stroke {
+            self.write_group(&mut out, stroke)?;
+            for line in lines {
+                let a = self.project(line.x.position());
+                let b = self.project(line.y.position());
+                if let (Some(a), Some(b)) = (a, b) {
+                    self.write_line(&mut out, a, b)?;
+                }
+            }
+            writeln!(out, "</g>")?;
+        }
+        writeln!(out, "</svg>")
+    }
+
+    /// Renders an indexed mesh, with polygons indexing into `vertices`.
+    ///
+    /// Every edge is drawn once, after the filled polygons.
+    pub fn write_indexed<W, V, P>(
+        &self,
+        mut out: W,
+        vertices: &[V],
+        polygons: &[P],
+    ) -> io::Result<()>
+    where
+        W: Write,
+        V: VertexAttributes,
+        P: EmitVertices<usize> + Clone,
+    {
+        let positions: Vec<Position> = vertices.iter().map(|v| v.position()).collect();
+        let projected: Vec<Option<Position>> = positions
+            .iter()
+            .map(|&p| self.camera.project(p, self.aspect()))
+            .collect();
+
+        let mut shapes = Vec::new();
+        let mut edges = Vec::new();
+        let mut seen = HashSet::new();
+        let mut face = Vec::with_capacity(4);
+        for p in polygons {
+            face.clear();
+            p.clone().emit_vertices(|i| face.push(i));
+            let corners: Option<Vec<Position>> = face.iter().map(|&i| projected[i]).collect();
+            let corners = match corners {
+                Some(corners) => corners,
+                None => continue,
+            };
+            if self.cull_back_faces && signed_area(&corners) <= 0. {
+                continue;
+            }
+
+            for (k, &a) in face.iter().enumerate() {
+                let b = face[(k + 1) % face.len()];
+                if seen.insert((a.min(b), a.max(b))) {
+                    edges.push((a, b));
+                }
+            }
+            if self.fill.is_some() {
+                let world: Vec<Vector3> = face.iter().map(|&i| positions[i].into()).collect();
+                shapes.push(self.shape(&world, &corners));
+            }
+        }
+
+        // painter's algorithm, farthest first
+        shapes.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+
+        self.write_header(&mut out)?;
+        if let Some(fill) = self.fill {
+            writeln!(out, "<g stroke=\"none\">")?;
+            for shape in &shapes {
+                let [r, g, b] = fill;
+                write!(out, "<polygon points=\"")?;
+                for (k, &(x, y)) in shape.points.iter().enumerate() {
+                    let separator = if k == 0 { "" } else { " " };
+                    write!(out, "{}{:.2},{:.2}", separator, x, y)?;
+                }
+                writeln!(
+                    out,
+                    "\" fill=\"rgb({},{},{})\"/>",
+                    channel(r * shape.light),
+                    channel(g * shape.light),
+                    channel(b * shape.light),
+                )?;
+            }
+            writeln!(out, "</g>")?;
+        }
+        if let Some(stroke) = &self.stroke {
+            self.write_group(&mut out, stroke)?;
+            for (a, b) in edges {
+                let a = self.to_pixels(projected[a].unwrap());
+                let b = self.to_pixels(projected[b].unwrap());
+                self.write_line(&mut out, a, b)?;
+            }
+            writeln!(out, "</g>")?;
+        }
+        writeln!(out, "</svg>")
+    }
+
+    fn aspect(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    fn project(&self, p: Position) -> Option<(f32, f32)> {
+        self.camera
+            .project(p, self.aspect())
+            .map(|p| self.to_pixels(p))
+    }
+
+    fn to_pixels(&self, p: Position) -> (f32, f32) {
+        (
+            (p.x + 1.) / 2. * self.width as f32,
+            (1. - p.y) / 2. * self.height as f32,
+        )
+    }
+
+    // flat shading from a light at the camera, with a little ambient light
+    fn shape(&self, world: &[Vector3], corners: &[Position]) -> Shape {
+        let mut normal = Vector3::new(0., 0., 0.);
+        let mut center = Vector3::new(0., 0., 0.);
+        for (k, &a) in world.iter().enumerate() {
+            normal += a.cross(world[(k + 1) % world.len()]);
+            center += a;
+        }
+        let center = center * (1. / world.len() as f32);
+        let lambert = if normal.magnitude_squared() > 0. {
+            normal
+                .normalized()
+                .dot(self.camera.towards_eye(center))
+                .abs()
+        } else {
+            0.
+        };
+        Shape {
+            points: corners.iter().map(|&p| self.to_pixels(p)).collect(),
+            depth: corners.iter().map(|p| p.z).sum::<f32>() / corners.len() as f32,
+            light: 0.2 + 0.8 * lambert,
+        }
+    }
+
+    fn write_header<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+            self.width, self.height
+        )
+    }
+
+    fn write_group<W: Write>(&self, out: &mut W, stroke: &str) -> io::Result<()> {
+        writeln!(
+            out,
+            "<g fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\">",
+            escape(stroke),
+            self.stroke_width
+        )
+    }
+
+    fn write_line<W: Write>(&self, out: &mut W, a: (f32, f32), b: (f32, f32)) -> io::Result<()> {
+        writeln!(
+            out,
+            "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\"/>",
+            a.0, a.1, b.0, b.1
+        )
+    }
+}
+
+// twice the area of a projected polygon, positive when it runs
+// counter-clockwise with `y` upwards
+fn signed_area(corners: &[Position]) -> f32 {
+    let mut area = 0.;
+    for (k, a) in corners.iter().enumerate() {
+        let b = corners[(k + 1) % corners.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+    area
+}
+
+fn channel(value: f32) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
+}
+
+// the stroke is free text placed inside an attribute
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
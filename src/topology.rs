@@ -0,0 +1,219 @@
+//! Topological diagnostics for indexed polygon meshes.
+
+use std::collections::BTreeMap;
+
+use crate::{EmitVertices, Line};
+
+/// A report on the topology of an indexed mesh: where it is open, where it
+/// is not a manifold, and what its Euler characteristic and genus are.
+///
+/// Connectivity is taken from the indices alone, so vertices that share a
+/// position but not an index count as separate vertices. Weld the mesh first
+/// when that is not wanted.
+///
+/// ```
+/// use genmesh::generators::{IndexedPolygon, Torus};
+/// use genmesh::TopologyReport;
+///
+/// let report = TopologyReport::new(Torus::new(1., 0.25, 16, 8).indexed_polygon_iter());
+/// assert!(report.is_closed() && report.is_manifold());
+/// assert_eq!(report.genus(), Some(1));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TopologyReport {
+    /// The number of vertices used by the faces that are not degenerate.
+    pub vertex_count: usize,
+    /// The number of distinct edges, regardless of direction.
+    pub edge_count: usize,
+    /// The number of faces that are not degenerate.
+    pub face_count: usize,
+    /// The edges that belong to a single face, in the direction they run
+    /// along that face.
+    pub boundary_edges: Vec<Line<usize>>,
+    /// The vertices around every hole, following the boundary edges.
+    pub boundary_loops: Vec<Vec<usize>>,
+    /// The edges that belong to more than two faces, with the smaller
+    /// vertex index first.
+    pub non_manifold_edges: Vec<Line<usize>>,
+    /// The vertices whose faces do not form a single fan, like the tip
+    /// shared by two cones, or the ends of a non-manifold edge.
+    pub non_manifold_vertices: Vec<usize>,
+    /// The indices of the faces that use the same vertex more than once.
+    /// These are left out of everything else in the report.
+    pub degenerate_faces: Vec<usize>,
+    /// The faces of every connected piece of the mesh, as indices into the
+    /// polygons the report was made from, ordered by their first face.
+    /// Faces that only share a vertex are in the same piece, as with
+    /// [`split_components`].
+    ///
+    /// [`split_components`]: fn.split_components.html
+    pub components: Vec<Vec<usize>>,
+}
+
+impl TopologyReport {
+    /// Analyzes the mesh made of `polygons`.
+    pub fn new<I, P>(polygons: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: EmitVertices<usize>,
+    {
+        let mut report = TopologyReport::default();
+        let mut faces: Vec<Vec<usize>> = Vec::new();
+        // the index of every face among all the polygons
+        let mut face_index = Vec::new();
+        for (i, p) in polygons.into_iter().enumerate() {
+            let mut face = Vec::with_capacity(4);
+            p.emit_vertices(|v| face.push(v));
+            let distinct = face.iter().enumerate().all(|(j, v)| !face[..j].contains(v));
+            if face.len() < 3 || !distinct {
+                report.degenerate_faces.push(i);
+            } else {
+                faces.push(face);
+                face_index.push(i);
+            }
+        }
+        report.face_count = faces.len();
+
+        let vertex_count = faces.iter().flatten().max().map_or(0, |&v| v + 1);
+        let mut used = vec![false; vertex_count];
+        // the first corner of every face, counting corners of all faces
+        let mut first_corner = Vec::with_capacity(faces.len());
+        let mut corners = 0;
+        // the faces of every edge, keyed by its sorted vertices
+        let mut edges: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+        for (f, face) in faces.iter().enumerate() {
+            first_corner.push(corners);
+            corners += face.len();
+            for (i, &a) in face.iter().enumerate() {
+                used[a] = true;
+                let b = face[(i + 1) % face.len()];
+                edges.entry((a.min(b), a.max(b))).or_default().push(f);
+            }
+        }
+        report.vertex_count = used.iter().filter(|&&u| u).count();
+        report.edge_count = edges.len();
+
+        let corner =
+            |f: usize, v: usize| first_corner[f] + faces[f].iter().position(|&u| u == v).unwrap();
+        let mut vertex_group: Vec<usize> = (0..vertex_count).collect();
+        // corners around the same vertex that are joined by manifold edges
+        let mut fan: Vec<usize> = (0..corners).collect();
+        let mut boundary_from: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+
+        for (&(a, b), sides) in &edges {
+            union(&mut vertex_group, a, b);
+            match sides[..] {
+                [f] => {
+                    // keep the direction the edge runs along its face
+                    let face = &faces[f];
+                    let after_a = face[(corner(f, a) - first_corner[f] + 1) % face.len()];
+                    let line = if after_a == b {
+                        Line::new(a, b)
+                    } else {
+                        Line::new(b, a)
+                    };
+                    boundary_from
+                        .entry(line.x)
+                        .or_default()
+                        .push(report.boundary_edges.len());
+                    report.boundary_edges.push(line);
+                }
+                [f, g] => {
+                    union(&mut fan, corner(f, a), corner(g, a));
+                    union(&mut fan, corner(f, b), corner(g, b));
+                }
+                _ => report.non_manifold_edges.push(Line::new(a, b)),
+            }
+        }
+
+        let mut fans = vec![Vec::new(); vertex_count];
+        for (f, face) in faces.iter().enumerate() {
+            for &v in face {
+                let root = find(&mut fan, corner(f, v));
+                if !fans[v].contains(&root) {
+                    fans[v].push(root);
+                }
+            }
+        }
+        report.non_manifold_vertices = (0..vertex_count).filter(|&v| fans[v].len() > 1).collect();
+
+        let mut component_of = BTreeMap::new();
+        for (f, face) in faces.iter().enumerate() {
+            let root = find(&mut vertex_group, face[0]);
+            let next = component_of.len();
+            let c = *component_of.entry(root).or_insert(next);
+            if c == report.components.len() {
+                report.components.push(Vec::new());
+            }
+            report.components[c].push(face_index[f]);
+        }
+
+        // chain the boundary edges into loops
+        let mut visited = vec![false; report.boundary_edges.len()];
+        for start in 0..report.boundary_edges.len() {
+            let mut ring = Vec::new();
+            let mut e = start;
+            while !visited[e] {
+                visited[e] = true;
+                let Line { x, y } = report.boundary_edges[e];
+                ring.push(x);
+                let next = boundary_from
+                    .get(&y)
+                    .and_then(|out| out.iter().find(|&&n| !visited[n]));
+                match next {
+                    Some(&n) => e = n,
+                    None => break,
+                }
+            }
+            if !ring.is_empty() {
+                report.boundary_loops.push(ring);
+            }
+        }
+
+        report
+    }
+
+    /// Returns `V - E + F`, the Euler characteristic of the mesh.
+    pub fn euler_characteristic(&self) -> isize {
+        self.vertex_count as isize - self.edge_count as isize + self.face_count as isize
+    }
+
+    /// Returns true if every edge belongs to two faces or more.
+    pub fn is_closed(&self) -> bool {
+        self.boundary_edges.is_empty()
+    }
+
+    /// Returns true if every edge belongs to at most two faces, and the
+    /// faces around every vertex form a single fan.
+    pub fn is_manifold(&self) -> bool {
+        self.non_manifold_edges.is_empty() && self.non_manifold_vertices.is_empty()
+    }
+
+    /// Returns the genus of the mesh, the number of handles summed over
+    /// all its components, or `None` if the mesh is not a manifold.
+    ///
+    /// Each component with genus `g` and `b` holes contributes `2 - 2g - b`
+    /// to the Euler characteristic.
+    pub fn genus(&self) -> Option<usize> {
+        if !self.is_manifold() {
+            return None;
+        }
+        let twice = 2 * self.components.len() as isize
+            - self.euler_characteristic()
+            - self.boundary_loops.len() as isize;
+        Some((twice / 2).max(0) as usize)
+    }
+}
+
+pub(crate) fn find(group: &mut [usize], mut i: usize) -> usize {
+    while group[i] != i {
+        group[i] = group[group[i]];
+        i = group[i];
+    }
+    i
+}
+
+pub(crate) fn union(group: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(group, a), find(group, b));
+    group[a.max(b)] = a.min(b);
+}
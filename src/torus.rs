@@ -4,7 +4,7 @@ use super::generators::{IndexedPolygon, SharedVertex};
 use super::{MapVertex, Quad, Vertex};
 use crate::math::Vector3;
 
-///
+/// Represents a torus lying in the XZ plane, centered at (0, 0, 0).
 #[derive(Clone, Copy)]
 pub struct Torus {
     idx: usize,
@@ -22,12 +22,7 @@ impl<T: Clone> EmitTriangles for Quad<T> {
     where
         F: FnMut(Triangle<T>),
     {
-        let &Quad {
-            ref x,
-            ref y,
-            ref z,
-            ref w,
-        } = self;
+        let Quad { x, y, z, w } = self;
         emit(Triangle::new(x.clone(), y.clone(), z.clone()));
         emit(Triangle::new(z.clone(), w.clone(), x.clone()));
     }
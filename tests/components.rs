@@ -0,0 +1,76 @@
+use genmesh::generators::{Cube, IcoSphere, IndexedPolygon, SharedVertex, Torus};
+use genmesh::{
+    split_components, EmitVertices, MapVertex, MergedMesh, Polygon, TopologyReport, Triangle,
+    Vertex,
+};
+
+fn merged() -> MergedMesh<Vertex, Polygon<usize>> {
+    let mut mesh = MergedMesh::new();
+    mesh.add_generator(&Cube::new());
+    mesh.add_generator(&IcoSphere::subdivide(1));
+    let torus = Torus::new(1., 0.25, 8, 6);
+    mesh.add(torus.shared_vertex_iter(), torus.indexed_polygon_iter());
+    mesh
+}
+
+#[test]
+fn merge_keeps_sources() {
+    let mesh = merged();
+    assert_eq!(mesh.sources, vec![0..6, 6..86, 86..134]);
+
+    let sphere = IcoSphere::subdivide(1);
+    let offset = Cube::new().shared_vertex_count();
+    for (merged, original) in mesh.source(1).iter().zip(sphere.indexed_polygon_iter()) {
+        assert_eq!(
+            *merged,
+            Polygon::PolyTri(original.map_vertex(|v| v + offset))
+        );
+        let mut v = Vec::new();
+        merged.emit_vertices(|i| v.push(mesh.vertices[i]));
+        assert_eq!(v[0], sphere.shared_vertex(original.x));
+    }
+}
+
+#[test]
+fn split_merged_mesh() {
+    let mesh = merged();
+    let components = split_components(&mesh.vertices, &mesh.polygons);
+
+    // the faces of the cube do not share vertices
+    assert_eq!(components.len(), 6 + 1 + 1);
+    for c in &components[..6] {
+        assert_eq!((c.vertices.len(), c.polygons.len()), (4, 1));
+    }
+    assert_eq!(components[6].vertices.len(), 42);
+    assert_eq!(components[6].polygon_remap, (6..86).collect::<Vec<_>>());
+    // the torus has a vertex that none of its quads use
+    assert_eq!(components[7].vertices.len(), 8 * 6);
+
+    for c in &components {
+        for (p, &i) in c.polygons.iter().zip(c.polygon_remap.iter()) {
+            assert_eq!(p.map_vertex(|v| c.vertex_remap[v]), mesh.polygons[i]);
+            let mut v = Vec::new();
+            p.emit_vertices(|i| v.push(i));
+            assert!(v
+                .iter()
+                .all(|&i| c.vertices[i] == mesh.vertices[c.vertex_remap[i]]));
+        }
+    }
+}
+
+#[test]
+fn split_agrees_with_topology() {
+    // two bow ties, each made of triangles that only share their middle
+    let vertices: Vec<usize> = (0..10).collect();
+    let triangles = vec![
+        Triangle::new(0, 1, 2),
+        Triangle::new(5, 6, 7),
+        Triangle::new(2, 3, 4),
+        Triangle::new(7, 8, 9),
+    ];
+    let components = split_components(&vertices, &triangles);
+    let report = TopologyReport::new(triangles.iter().copied());
+    assert_eq!(report.components, vec![vec![0, 2], vec![1, 3]]);
+    let remaps: Vec<_> = components.iter().map(|c| c.polygon_remap.clone()).collect();
+    assert_eq!(remaps, report.components);
+}
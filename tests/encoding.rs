@@ -0,0 +1,184 @@
+use genmesh::encoding::*;
+use genmesh::generators::{IcoSphere, SharedVertex, Torus};
+use genmesh::{Attribute, Format, Normal, Position, Vertex, VertexLayout};
+
+#[test]
+fn half_floats() {
+    assert_eq!(encode_f16(0.), 0);
+    assert_eq!(encode_f16(-0.), 0x8000);
+    assert_eq!(encode_f16(1.), 0x3c00);
+    assert_eq!(encode_f16(-2.), 0xc000);
+    assert_eq!(encode_f16(65504.), 0x7bff);
+    // too large, or rounding up past the largest half float
+    assert_eq!(encode_f16(1e6), 0x7c00);
+    assert_eq!(encode_f16(65520.), 0x7c00);
+    assert_eq!(encode_f16(f32::NEG_INFINITY), 0xfc00);
+    assert!(decode_f16(encode_f16(f32::NAN)).is_nan());
+    // subnormals, and values too small for them
+    assert_eq!(encode_f16(2f32.powi(-24)), 1);
+    assert_eq!(encode_f16(2f32.powi(-26)), 0);
+    // ties round to even
+    assert_eq!(encode_f16(1. + 2f32.powi(-11)), 0x3c00);
+    assert_eq!(encode_f16(1. + 3. * 2f32.powi(-11)), 0x3c02);
+
+    // every half float survives a round trip
+    for half in 0..=u16::MAX {
+        let value = decode_f16(half);
+        if !value.is_nan() {
+            assert_eq!(encode_f16(value), half, "{:#06x}", half);
+        }
+    }
+}
+
+#[test]
+fn normalized_integers() {
+    assert_eq!(encode_snorm(1., 8), 127);
+    assert_eq!(encode_snorm(-2., 8), -127);
+    assert_eq!(decode_snorm(-128, 8), -1.);
+    assert_eq!(encode_snorm(1., 32), i32::MAX);
+    assert_eq!(encode_snorm(-1., 32), -i32::MAX);
+    assert_eq!(decode_snorm(i32::MAX, 32), 1.);
+    assert_eq!(decode_snorm(i32::MIN, 32), -1.);
+    assert_eq!(encode_snorm(-1., 2), -1);
+    assert_eq!(encode_unorm(0.5, 8), 128);
+    assert_eq!(encode_unorm(1., 32), u32::MAX);
+    assert_eq!(encode_unorm(0.5, 1), 1);
+    assert_eq!(decode_unorm(u32::MAX, 32), 1.);
+    assert_eq!(decode_unorm(1, 1), 1.);
+    assert_eq!(encode_unorm16(1.5), u16::MAX);
+    assert_eq!(decode_unorm16(u16::MAX), 1.);
+    for &t in &[0., 0.1, 0.25, 0.7, 1.] {
+        assert!((decode_unorm16(encode_unorm16(t)) - t).abs() <= 0.5 / 65535.);
+    }
+
+    let packed = encode_snorm10_10_10_2([1., -1., 0., -1.]);
+    assert_eq!(packed, 511 | 0x201 << 10 | 0b11 << 30);
+    assert_eq!(decode_snorm10_10_10_2(packed), [1., -1., 0., -1.]);
+    let [x, y, z, w] = decode_snorm10_10_10_2(encode_snorm10_10_10_2([0.3, -0.6, 0.9, 0.8]));
+    assert!((x - 0.3).abs() < 1e-3 && (y + 0.6).abs() < 1e-3 && (z - 0.9).abs() < 1e-3);
+    assert_eq!(w, 1.);
+}
+
+fn distance(a: Normal, b: Normal) -> f32 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+#[test]
+#[should_panic]
+fn unorm_without_bits() {
+    decode_unorm(0, 0);
+}
+
+#[test]
+#[should_panic]
+fn unorm_too_many_bits() {
+    encode_unorm(1., 64);
+}
+
+#[test]
+#[should_panic]
+fn snorm_single_bit() {
+    decode_snorm(0, 1);
+}
+
+#[test]
+fn octahedral_normals() {
+    let normals: Vec<Normal> = IcoSphere::subdivide(4)
+        .shared_vertex_iter()
+        .map(|v| v.normal)
+        .collect();
+    let worst16 = normals
+        .iter()
+        .map(|&n| distance(n, decode_octahedral16(encode_octahedral16(n))))
+        .fold(0f32, f32::max);
+    let worst8 = normals
+        .iter()
+        .map(|&n| distance(n, decode_octahedral8(encode_octahedral8(n))))
+        .fold(0f32, f32::max);
+    assert!(worst16 < 1e-4, "{}", worst16);
+    assert!(worst8 < 0.02, "{}", worst8);
+
+    // the axes are exact, including the folded lower half
+    for &n in &[[0., 0., 1.], [0., 0., -1.], [1., 0., 0.], [0., -1., 0.]] {
+        let n = Normal::from(n);
+        assert_eq!(decode_octahedral16(encode_octahedral16(n)), n);
+    }
+    assert_eq!(
+        decode_octahedral8(encode_octahedral8([0., 0., 0.].into())),
+        [0., 0., 1.].into()
+    );
+}
+
+#[test]
+fn quantized_positions() {
+    let min = Position::from([-2., 0., 1.]);
+    let max = Position::from([2., 1., 1.]);
+    assert_eq!(encode_quantized(min, min, max), [0, 0, 0]);
+    assert_eq!(encode_quantized(max, min, max), [u16::MAX, u16::MAX, 0]);
+    let p = Position::from([0.3, 0.6, 1.]);
+    let d = decode_quantized(encode_quantized(p, min, max), min, max);
+    assert!((d.x - p.x).abs() <= 2. / 65535. && (d.y - p.y).abs() <= 0.5 / 65535.);
+    assert_eq!(d.z, 1.);
+}
+
+#[test]
+fn compact_packing() {
+    let vertices: Vec<Vertex> = Torus::new(1., 0.25, 16, 8).shared_vertex_iter().collect();
+    let layout = VertexLayout::interleaved(&[
+        (Attribute::Position, Format::Unorm16x4),
+        (Attribute::Normal, Format::Octahedral8),
+    ]);
+    assert_eq!(layout.stride(0), 12);
+    let packed = layout.pack(&vertices);
+    assert_eq!(packed.buffers[0].len(), 12 * vertices.len());
+
+    let (min, max) = packed.position_bounds.unwrap();
+    let lowest = vertices
+        .iter()
+        .map(|v| v.pos.z)
+        .fold(f32::INFINITY, f32::min);
+    let highest = vertices
+        .iter()
+        .map(|v| v.pos.x)
+        .fold(f32::NEG_INFINITY, f32::max);
+    assert_eq!((min.z, max.x), (lowest, highest));
+    // half a step along each axis of the box
+    let step = |min: f32, max: f32| (max - min) / 65535. / 2.;
+    let bound =
+        (step(min.x, max.x).powi(2) + step(min.y, max.y).powi(2) + step(min.z, max.z).powi(2))
+            .sqrt();
+    assert!(packed.max_errors[0] > 0. && packed.max_errors[0] <= bound * 1.01);
+    assert!(packed.max_errors[1] > 0. && packed.max_errors[1] < 0.02);
+
+    // the error is measured from the bytes the shader sees
+    let first = &packed.buffers[0][..12];
+    let x = u16::from_le_bytes([first[0], first[1]]);
+    let decoded = min.x + decode_unorm16(x) * (max.x - min.x);
+    assert!((decoded - vertices[0].pos.x).abs() <= packed.max_errors[0]);
+    assert_eq!(u16::from_le_bytes([first[6], first[7]]), u16::MAX);
+
+    // two components of a position are quantized in the same box
+    let packed = VertexLayout::planar(&[(Attribute::Position, Format::Unorm16x2)]).pack(&vertices);
+    assert_eq!(packed.position_bounds, Some((min, max)));
+    assert!(packed.max_errors[0] > 0. && packed.max_errors[0] <= bound * 1.01);
+
+    let planar = VertexLayout::planar(&[
+        (Attribute::Position, Format::Float16x4),
+        (Attribute::Normal, Format::Snorm10_10_10_2),
+        (Attribute::Normal, Format::Octahedral16),
+        (Attribute::Normal, Format::Float32x3),
+    ]);
+    let packed = planar.pack(&vertices);
+    assert_eq!(packed.position_bounds, None);
+    assert!(packed.max_errors[0] < 1e-3);
+    assert!(packed.max_errors[1] < 5e-3);
+    assert!(packed.max_errors[2] < 1e-4);
+    assert_eq!(packed.max_errors[3], 0.);
+    assert_eq!(
+        packed.buffers.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![8, 4, 4, 12]
+            .into_iter()
+            .map(|s| s * vertices.len())
+            .collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,187 @@
+use genmesh::generators::{IndexedPolygon, Plane, SharedVertex, SphereUv};
+use genmesh::gltf::GltfWriter;
+use genmesh::{EmitTriangles, Position, Vertex, VertexAttributes};
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+#[test]
+fn gltf_json_and_buffer() {
+    let sphere = SphereUv::new(8, 4);
+    let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+    let polygons: Vec<_> = sphere.indexed_polygon_iter().collect();
+
+    let mut json = Vec::new();
+    let mut bin = Vec::new();
+    GltfWriter::new()
+        .name("sphere \"uv\"")
+        .write_gltf(&mut json, &mut bin, "sphere.bin", &vertices, &polygons)
+        .unwrap();
+    let json = String::from_utf8(json).unwrap();
+
+    assert!(json.contains("\"name\":\"sphere \\\"uv\\\"\""));
+    assert!(json.contains("\"attributes\":{\"POSITION\":0,\"NORMAL\":1}"));
+    assert!(json.contains("\"indices\":2"));
+    assert!(json.contains("\"min\":[-1,-1,-1],\"max\":[1,1,1]"));
+    assert!(json.contains(&format!(
+        "\"buffers\":[{{\"byteLength\":{},\"uri\":\"sphere.bin\"}}]",
+        bin.len()
+    )));
+
+    // both float streams come first, followed by u16 indices
+    let mut triangles = 0;
+    for p in &polygons {
+        p.emit_triangles(|_| triangles += 1);
+    }
+    let floats = vertices.len() * 6 * 4;
+    assert_eq!(bin.len(), floats + (triangles * 3 * 2usize).div_ceil(4) * 4);
+    assert!(json.contains("\"componentType\":5123"));
+}
+
+#[test]
+fn gltf_normals_can_be_left_out() {
+    let plane = Plane::new();
+    let vertices: Vec<Vertex> = plane.shared_vertex_iter().collect();
+    let quads: Vec<_> = plane.indexed_polygon_iter().collect();
+
+    let mut json = Vec::new();
+    let mut bin = Vec::new();
+    GltfWriter::new()
+        .normals(false)
+        .write_gltf(&mut json, &mut bin, "plane.bin", &vertices, &quads)
+        .unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains("\"attributes\":{\"POSITION\":0}"));
+    assert!(!json.contains("\"name\""));
+    // the z of the plane is always 0
+    assert!(json.contains("\"min\":[-1,-1,0],\"max\":[1,1,0]"));
+    // two triangles of u16 indices, padded to four bytes
+    assert_eq!(bin.len(), 4 * 3 * 4 + 12);
+}
+
+#[test]
+fn glb_chunks() {
+    let plane = Plane::subdivide(300, 300);
+    let vertices: Vec<Position> = plane.shared_vertex_iter().map(|v| v.pos).collect();
+    let quads: Vec<_> = plane.indexed_polygon_iter().collect();
+    assert!(vertices.len() > u16::MAX as usize);
+
+    let mut glb = Vec::new();
+    GltfWriter::new()
+        .write_glb(&mut glb, &vertices, &quads)
+        .unwrap();
+    assert_eq!(&glb[..4], b"glTF");
+    assert_eq!(u32_at(&glb, 4), 2);
+    assert_eq!(u32_at(&glb, 8) as usize, glb.len());
+
+    let json_length = u32_at(&glb, 12) as usize;
+    assert_eq!(json_length % 4, 0);
+    assert_eq!(&glb[16..20], b"JSON");
+    let json = std::str::from_utf8(&glb[20..20 + json_length]).unwrap();
+    // the vertices need 32-bit indices, and the buffer has no uri
+    assert!(json.contains("\"componentType\":5125"));
+    assert!(!json.contains("uri"));
+
+    let bin = 20 + json_length;
+    let bin_length = u32_at(&glb, bin) as usize;
+    assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+    assert_eq!(bin + 8 + bin_length, glb.len());
+    assert_eq!(bin_length, vertices.len() * 12 + quads.len() * 6 * 4);
+
+    // the first index follows the positions
+    let first = bin + 8 + vertices.len() * 12;
+    assert_eq!(u32_at(&glb, first) as usize, quads[0].x);
+}
+
+#[derive(Clone, Copy)]
+struct Textured {
+    pos: Position,
+    uv: [f32; 2],
+}
+
+impl VertexAttributes for Textured {
+    fn position(&self) -> Position {
+        self.pos
+    }
+
+    fn tex_coord(&self) -> Option<genmesh::TexCoord> {
+        Some(self.uv.into())
+    }
+
+    fn tangent(&self) -> Option<genmesh::Tangent> {
+        Some([1., 0., 0., -1.].into())
+    }
+}
+
+#[test]
+fn gltf_save_files() {
+    let plane = Plane::new();
+    let vertices: Vec<Textured> = plane
+        .shared_vertex_iter()
+        .map(|v| Textured {
+            pos: v.pos,
+            uv: [(v.pos.x + 1.) / 2., (1. - v.pos.y) / 2.],
+        })
+        .collect();
+    let quads: Vec<_> = plane.indexed_polygon_iter().collect();
+
+    let dir = std::env::temp_dir().join(format!("genmesh-gltf-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    GltfWriter::new()
+        .save(dir.join("plane.gltf"), &vertices, &quads)
+        .unwrap();
+    GltfWriter::new()
+        .save(dir.join("plane.glb"), &vertices, &quads)
+        .unwrap();
+
+    let json = std::fs::read_to_string(dir.join("plane.gltf")).unwrap();
+    assert!(json.contains("\"attributes\":{\"POSITION\":0,\"TANGENT\":1,\"TEXCOORD_0\":2}"));
+    assert!(json.contains("\"uri\":\"plane.bin\""));
+    assert!(json.contains("\"min\":[0,0],\"max\":[1,1]"));
+    let bin = std::fs::read(dir.join("plane.bin")).unwrap();
+    assert_eq!(bin.len(), 4 * (12 + 16 + 8) + 12);
+    let glb = std::fs::read(dir.join("plane.glb")).unwrap();
+    assert_eq!(&glb[..4], b"glTF");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn gltf_rejects_empty_meshes() {
+    let vertices: Vec<Position> = Vec::new();
+    let quads: Vec<genmesh::Quad<usize>> = Vec::new();
+    let err = GltfWriter::new()
+        .write_glb(Vec::new(), &vertices, &quads)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn gltf_rejects_non_finite_positions() {
+    let plane = Plane::new();
+    let mut vertices: Vec<Vertex> = plane.shared_vertex_iter().collect();
+    let quads: Vec<_> = plane.indexed_polygon_iter().collect();
+    vertices[1].pos.y = f32::NAN;
+    let err = GltfWriter::new()
+        .write_glb(Vec::new(), &vertices, &quads)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn gltf_rejects_bin_paths() {
+    let plane = Plane::new();
+    let vertices: Vec<Vertex> = plane.shared_vertex_iter().collect();
+    let quads: Vec<_> = plane.indexed_polygon_iter().collect();
+    let path = std::env::temp_dir().join(format!("genmesh-gltf-{}.bin", std::process::id()));
+    let err = GltfWriter::new()
+        .save(&path, &vertices, &quads)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!path.exists());
+}
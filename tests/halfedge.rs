@@ -0,0 +1,177 @@
+use genmesh::generators::{Cube, IcoSphere, IndexedPolygon, Plane, SharedVertex};
+use genmesh::{
+    HalfEdgeError, HalfEdgeMesh, Indexer, Line, LruIndexer, MapToVertices, Polygon, Position,
+    Triangle, Vertex,
+};
+
+fn welded_cube() -> HalfEdgeMesh<Position> {
+    let mut vertices = Vec::new();
+    let quads: Vec<_> = {
+        let mut indexer = LruIndexer::new(8, |_, v| vertices.push(v));
+        Cube::new().vertex(|v| indexer.index(v.pos)).collect()
+    };
+    HalfEdgeMesh::new(vertices, &quads).unwrap()
+}
+
+fn euler_characteristic<T>(mesh: &HalfEdgeMesh<T>) -> isize {
+    let v = mesh.vertex_indices().count() as isize;
+    let e = mesh.half_edge_indices().count() as isize / 2;
+    let f = mesh.face_indices().count() as isize;
+    v - e + f
+}
+
+#[test]
+fn half_edge_closed_quads() {
+    let mesh = welded_cube();
+    assert!(mesh.is_valid());
+    assert_eq!(mesh.face_indices().count(), 6);
+    assert_eq!(mesh.half_edge_indices().count(), 24);
+    assert!(mesh.boundary_loops().is_empty());
+    assert_eq!(euler_characteristic(&mesh), 2);
+    for v in mesh.vertex_indices() {
+        assert!(!mesh.is_boundary_vertex(v));
+        assert_eq!(mesh.one_ring(v).count(), 3);
+        assert_eq!(mesh.vertex_faces(v).count(), 3);
+    }
+
+    let (vertices, polygons) = mesh.into_indexed();
+    assert_eq!(vertices.len(), 8);
+    assert!(polygons.iter().all(|p| matches!(p, Polygon::PolyQuad(_))));
+}
+
+#[test]
+fn half_edge_one_ring_and_boundary() {
+    let plane = Plane::subdivide(2, 2);
+    let vertices: Vec<Vertex> = plane.shared_vertex_iter().collect();
+    let quads: Vec<_> = plane.indexed_polygon_iter().collect();
+    let mesh = HalfEdgeMesh::new(vertices, &quads).unwrap();
+    assert!(mesh.is_valid());
+
+    // the center vertex is surrounded counter-clockwise by its neighbors
+    let center = (0..9)
+        .find(|&v| mesh.vertex(v).pos == [0., 0., 0.].into())
+        .unwrap();
+    let ring: Vec<_> = mesh.one_ring(center).map(|v| mesh.vertex(v).pos).collect();
+    assert_eq!(ring.len(), 4);
+    for (i, a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % 4];
+        assert!(a.x * b.y - a.y * b.x > 0.);
+    }
+
+    let loops = mesh.boundary_loops();
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 8);
+    assert!(!loops[0].contains(&center));
+    assert!(loops[0].iter().all(|&v| mesh.is_boundary_vertex(v)));
+    // the first outgoing half-edge of a boundary vertex is on the boundary
+    let h = mesh.vertex_half_edge(loops[0][0]).unwrap();
+    assert_eq!(mesh.face(h), None);
+    assert_eq!(mesh.target(h), loops[0][1]);
+}
+
+#[test]
+fn half_edge_edits_stay_valid() {
+    let sphere = IcoSphere::subdivide(1);
+    let vertices: Vec<Position> = sphere.shared_vertex_iter().map(|v| v.pos).collect();
+    let triangles: Vec<_> = sphere.indexed_polygon_iter().collect();
+    let mut mesh = HalfEdgeMesh::new(vertices, &triangles).unwrap();
+    assert!(mesh.is_valid());
+    assert_eq!(euler_characteristic(&mesh), 2);
+
+    let (mut flips, mut collapses) = (0, 0);
+    for h in 0..60 {
+        if mesh.half_edge_indices().any(|e| e == h) && mesh.flip_edge(h) {
+            flips += 1;
+            assert!(mesh.is_valid());
+        }
+    }
+    for h in 0..40 {
+        let h = mesh.half_edge_indices().nth(h * 3).unwrap();
+        let (a, b) = (mesh.origin(h), mesh.target(h));
+        let m = mesh.split_edge(h, [0., 0., 0.].into()).unwrap();
+        assert!(mesh.is_valid());
+        assert!(mesh.find_half_edge(a, m).is_some());
+        assert!(mesh.find_half_edge(m, b).is_some());
+        assert_eq!(mesh.one_ring(m).count(), 4);
+    }
+    for h in 0..400 {
+        if mesh.half_edge_indices().any(|e| e == h) && mesh.collapse_edge(h) {
+            collapses += 1;
+            assert!(mesh.is_valid());
+        }
+    }
+    assert!(flips > 0 && collapses > 0);
+    assert_eq!(euler_characteristic(&mesh), 2);
+    assert!(mesh.boundary_loops().is_empty());
+
+    let faces = mesh.face_indices().count();
+    let (vertices, polygons) = mesh.into_indexed();
+    assert_eq!(polygons.len(), faces);
+    // a closed triangle mesh of genus 0 has two more vertices than half its faces
+    assert_eq!(vertices.len(), faces / 2 + 2);
+}
+
+#[test]
+fn half_edge_open_edits() {
+    let plane = Plane::subdivide(3, 3);
+    let vertices: Vec<Vertex> = plane.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = plane
+        .indexed_polygon_iter()
+        .flat_map(|q| vec![Triangle::new(q.x, q.y, q.z), Triangle::new(q.z, q.w, q.x)])
+        .collect();
+    let mut mesh = HalfEdgeMesh::new(vertices, &triangles).unwrap();
+
+    let boundary: Vec<_> = mesh
+        .half_edge_indices()
+        .filter(|&h| mesh.face(h).is_none())
+        .collect();
+    for &h in boundary.iter().take(4) {
+        let m = mesh.split_edge(
+            h,
+            Vertex {
+                pos: [0., 0., 0.].into(),
+                normal: [0., 0., 1.].into(),
+            },
+        );
+        assert!(mesh.is_boundary_vertex(m.unwrap()));
+        assert!(mesh.is_valid());
+    }
+    assert_eq!(mesh.boundary_loops()[0].len(), 16);
+    // a boundary edge can not be flipped
+    assert!(!mesh.flip_edge(boundary[5]));
+
+    let mut collapses = 0;
+    for h in 0..200 {
+        if mesh.half_edge_indices().any(|e| e == h) && mesh.collapse_edge(h) {
+            collapses += 1;
+            assert!(mesh.is_valid());
+        }
+    }
+    assert!(collapses > 0);
+    assert_eq!(euler_characteristic(&mesh), 1);
+    assert_eq!(mesh.boundary_loops().len(), 1);
+}
+
+#[test]
+fn half_edge_errors() {
+    let vertices = vec![(); 5];
+    let fin = [
+        Triangle::new(0, 1, 2),
+        Triangle::new(1, 0, 3),
+        Triangle::new(0, 1, 4),
+    ];
+    assert_eq!(
+        HalfEdgeMesh::new(vertices.clone(), &fin).unwrap_err(),
+        HalfEdgeError::NonManifoldEdge(Line::new(0, 1))
+    );
+    assert_eq!(
+        HalfEdgeMesh::new(vertices.clone(), &[Triangle::new(0, 1, 1)]).unwrap_err(),
+        HalfEdgeError::DegenerateFace(0)
+    );
+    // two triangles touching at a single vertex
+    let bowtie = [Triangle::new(0, 1, 2), Triangle::new(0, 3, 4)];
+    assert_eq!(
+        HalfEdgeMesh::new(vertices, &bowtie).unwrap_err(),
+        HalfEdgeError::NonManifoldVertex(0)
+    );
+}
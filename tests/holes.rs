@@ -0,0 +1,99 @@
+use genmesh::generators::{Cylinder, IcoSphere, IndexedPolygon, SharedVertex};
+use genmesh::{FillMethod, HoleFiller, Polygon, Position, TopologyReport, Triangle};
+
+fn area(vertices: &[Position], triangles: &[Triangle<usize>]) -> f32 {
+    triangles
+        .iter()
+        .map(|t| {
+            let [a, b, c] = [vertices[t.x], vertices[t.y], vertices[t.z]];
+            let u = [b.x - a.x, b.y - a.y, b.z - a.z];
+            let v = [c.x - a.x, c.y - a.y, c.z - a.z];
+            let n = [
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0],
+            ];
+            (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() / 2.
+        })
+        .sum()
+}
+
+#[test]
+fn fill_sphere_holes() {
+    let sphere = IcoSphere::subdivide(2);
+    let vertices: Vec<Position> = sphere.shared_vertex_iter().map(|v| v.pos).collect();
+    let full: Vec<Triangle<usize>> = sphere.indexed_polygon_iter().collect();
+
+    // cut out the triangles around one vertex, and a single triangle elsewhere
+    let around = full[0].x;
+    let touches = |t: &Triangle<usize>, v: usize| t.x == v || t.y == v || t.z == v;
+    let ring: Vec<usize> = full
+        .iter()
+        .filter(|t| touches(t, around))
+        .flat_map(|t| vec![t.x, t.y, t.z])
+        .collect();
+    let far = full
+        .iter()
+        .position(|t| ring.iter().all(|&v| !touches(t, v)))
+        .unwrap();
+    let open: Vec<Triangle<usize>> = full
+        .iter()
+        .enumerate()
+        .filter(|&(i, t)| i != far && !touches(t, around))
+        .map(|(_, &t)| t)
+        .collect();
+    assert_eq!(
+        TopologyReport::new(open.iter().copied())
+            .boundary_loops
+            .len(),
+        2
+    );
+
+    let mut fan_area = 0.;
+    for &method in &[FillMethod::Fan, FillMethod::MinimumArea] {
+        let fill = HoleFiller::new()
+            .method(method)
+            .fill(&vertices, &open, |&p| p);
+        // a pentagon takes three triangles, and a triangle one
+        assert_eq!(fill.len(), 4);
+
+        let mut closed = open.clone();
+        closed.extend(fill.iter().copied());
+        let report = TopologyReport::new(closed);
+        assert!(report.is_closed() && report.is_manifold());
+        assert_eq!(report.genus(), Some(0));
+        // every edge is used once in each direction
+        assert_eq!(report.edge_count * 2, (open.len() + fill.len()) * 3);
+
+        match method {
+            FillMethod::Fan => fan_area = area(&vertices, &fill),
+            FillMethod::MinimumArea => assert!(area(&vertices, &fill) <= fan_area + 1e-6),
+        }
+    }
+}
+
+#[test]
+fn fill_respects_max_edges() {
+    let cylinder = Cylinder::subdivide(8, 2);
+    let vertices: Vec<Position> = cylinder.shared_vertex_iter().map(|v| v.pos).collect();
+    // keep only the side of the cylinder, which has an open rim at each end
+    let tube: Vec<Polygon<usize>> = cylinder
+        .indexed_polygon_iter()
+        .filter(|p| matches!(p, Polygon::PolyQuad(_)))
+        .collect();
+    assert_eq!(
+        TopologyReport::new(tube.iter().copied())
+            .boundary_loops
+            .len(),
+        2
+    );
+
+    let filler = HoleFiller::new().max_edges(7);
+    assert!(filler.fill(&vertices, &tube, |&p| p).is_empty());
+
+    let fill = filler.max_edges(8).fill(&vertices, &tube, |&p| p);
+    assert_eq!(fill.len(), 12);
+    let mut closed = tube.clone();
+    closed.extend(fill.into_iter().map(Polygon::PolyTri));
+    assert!(TopologyReport::new(closed).is_closed());
+}
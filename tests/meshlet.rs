@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use cgmath::InnerSpace;
+use genmesh::generators::{IcoSphere, IndexedPolygon, Plane, SharedVertex};
+use genmesh::{MapVertex, Meshlet, MeshletBuilder, Triangle, Triangulate, Vertex};
+
+fn vec3(v: genmesh::Position) -> cgmath::Vector3<f32> {
+    cgmath::vec3(v.x, v.y, v.z)
+}
+
+/// Checks that every triangle lands in exactly one meshlet, that the
+/// limits are respected and that the bounds hold.
+fn check(vertices: &[Vertex], triangles: &[Triangle<usize>], meshlets: &[Meshlet]) {
+    let mut count = HashMap::new();
+    for t in triangles {
+        *count.entry((t.x, t.y, t.z)).or_insert(0) += 1;
+    }
+
+    for m in meshlets {
+        assert!(m.vertices.len() <= 64);
+        assert!(m.triangles.len() <= 124);
+
+        for t in &m.triangles {
+            let t = t.map_vertex(|i| m.vertices[i as usize]);
+            let c = count.get_mut(&(t.x, t.y, t.z)).expect("unknown triangle");
+            assert!(*c > 0, "triangle {:?} appears twice", t);
+            *c -= 1;
+
+            let [a, b, c] = [t.x, t.y, t.z].map(|i| vec3(vertices[i].pos));
+            let normal = (b - a).cross(c - a).normalize();
+            let min_dot = (1. - m.cone_cutoff * m.cone_cutoff).sqrt();
+            assert!(normal.dot(vec3(m.cone_axis)) >= min_dot - 1e-4);
+        }
+
+        for &v in &m.vertices {
+            let d = (vec3(vertices[v].pos) - vec3(m.center)).magnitude();
+            assert!(d <= m.radius + 1e-4);
+        }
+    }
+
+    assert!(count.values().all(|&c| c == 0), "triangle missing");
+}
+
+#[test]
+fn meshlet_icosphere() {
+    let sphere = IcoSphere::subdivide(4);
+    let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = sphere.indexed_polygon_iter().collect();
+
+    let meshlets = MeshletBuilder::new(64, 124).build(&vertices, triangles.clone(), |v| v.pos);
+    check(&vertices, &triangles, &meshlets);
+    // a well packed sphere needs roughly one meshlet per 64 triangles
+    assert!(meshlets.len() < triangles.len() / 50);
+}
+
+#[test]
+fn meshlet_plane() {
+    let plane = Plane::subdivide(20, 20);
+    let vertices: Vec<Vertex> = plane.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = plane.indexed_polygon_iter().triangulate().collect();
+
+    let meshlets = MeshletBuilder::new(64, 124).build(&vertices, triangles.clone(), |v| v.pos);
+    check(&vertices, &triangles, &meshlets);
+    // a flat patch has a degenerate normal cone that culls everything behind it
+    for m in &meshlets {
+        assert_eq!(m.cone_axis, [0., 0., 1.].into());
+        assert!(m.cone_cutoff.abs() < 1e-3);
+    }
+}
@@ -0,0 +1,141 @@
+use std::f32::consts::PI;
+
+use genmesh::generators::{Cube, IcoSphere, IndexedPolygon, SharedVertex};
+use genmesh::{
+    flat_shade, flat_shade_indexed, Indexer, LruIndexer, MapToVertices, NormalWeight, Position,
+    ProvokingVertex, SmoothNormals, Triangle, Triangulate, Vertex, Vertices,
+};
+
+/// The cube with its 8 corners shared between the faces.
+fn welded_cube() -> (Vec<Position>, Vec<Triangle<usize>>) {
+    let mut vertices = Vec::new();
+    let triangles = {
+        let mut indexer = LruIndexer::new(8, |_, v| vertices.push(v));
+        Cube::new()
+            .triangulate()
+            .vertex(|v| indexer.index(v.pos))
+            .collect()
+    };
+    (vertices, triangles)
+}
+
+fn with_normal(&pos: &Position, normal: genmesh::Normal) -> Vertex {
+    Vertex { pos, normal }
+}
+
+#[test]
+fn smooth_normals_split_hard_edges() {
+    let (vertices, triangles) = welded_cube();
+
+    let (out, remapped) =
+        SmoothNormals::new(PI / 6.).generate(&vertices, &triangles, |&p| p, with_normal);
+    // every corner splits into one vertex per face
+    assert_eq!(out.len(), 24);
+    assert_eq!(remapped.len(), triangles.len());
+    for (t, r) in triangles.iter().zip(remapped.iter()) {
+        assert_eq!(vertices[t.x], out[r.x].pos);
+        assert_eq!(vertices[t.y], out[r.y].pos);
+        assert_eq!(vertices[t.z], out[r.z].pos);
+        // all three corners share the flat face normal
+        assert_eq!(out[r.x].normal, out[r.y].normal);
+        assert_eq!(out[r.x].normal, out[r.z].normal);
+        let n = out[r.x].normal;
+        assert_eq!(n.x.abs() + n.y.abs() + n.z.abs(), 1.);
+    }
+}
+
+#[test]
+fn smooth_normals_weighting() {
+    let (vertices, triangles) = welded_cube();
+    let diagonal = |v: &Vertex| {
+        let p = v.pos;
+        let n = v.normal;
+        let expected = [p.x, p.y, p.z].map(|c| c / 3f32.sqrt());
+        [n.x, n.y, n.z]
+            .iter()
+            .zip(expected.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-5)
+    };
+
+    // every face covers a right angle of each corner, however it is split
+    let (out, _) = SmoothNormals::new(PI).generate(&vertices, &triangles, |&p| p, with_normal);
+    assert_eq!(out.len(), 8);
+    assert!(out.iter().all(diagonal));
+
+    // while counting triangles favors the faces split at the corner
+    let (out, _) = SmoothNormals::new(PI)
+        .weight(NormalWeight::Uniform)
+        .generate(&vertices, &triangles, |&p| p, with_normal);
+    assert_eq!(out.len(), 8);
+    assert!(!out.iter().all(diagonal));
+}
+
+#[test]
+fn smooth_normals_sphere() {
+    let sphere = IcoSphere::subdivide(2);
+    let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = sphere.indexed_polygon_iter().collect();
+
+    for &weight in &[
+        NormalWeight::Uniform,
+        NormalWeight::Area,
+        NormalWeight::Angle,
+    ] {
+        let (out, _) = SmoothNormals::new(PI / 3.).weight(weight).generate(
+            &vertices,
+            &triangles,
+            |v| v.pos,
+            |v, normal| Vertex { normal, ..*v },
+        );
+        assert_eq!(out.len(), vertices.len());
+        for v in &out {
+            let d = v.pos.x * v.normal.x + v.pos.y * v.normal.y + v.pos.z * v.normal.z;
+            assert!(d > 0.99, "normal {:?} at {:?}", v.normal, v.pos);
+        }
+    }
+}
+
+#[test]
+fn flat_shade_expands_faces() {
+    let (vertices, triangles) = welded_cube();
+
+    let flat = flat_shade(&vertices, &triangles, |&p| p, with_normal);
+    assert_eq!(flat.len(), triangles.len());
+    for (t, f) in triangles.iter().zip(flat.iter()) {
+        assert_eq!(vertices[t.x], f.x.pos);
+        assert_eq!(vertices[t.y], f.y.pos);
+        assert_eq!(vertices[t.z], f.z.pos);
+        assert_eq!(f.x.normal, f.y.normal);
+        assert_eq!(f.x.normal, f.z.normal);
+    }
+    assert_eq!(flat.into_iter().vertices().count(), 36);
+}
+
+#[test]
+fn flat_shade_indexed_provoking_vertex() {
+    let (vertices, triangles) = welded_cube();
+    let flat = flat_shade(&vertices, &triangles, |&p| p, with_normal);
+
+    for &(provoking, corner) in &[(ProvokingVertex::First, 0), (ProvokingVertex::Last, 2)] {
+        let (out, remapped) =
+            flat_shade_indexed(&vertices, &triangles, provoking, |&p| p, with_normal);
+        // far fewer vertices than the 36 of the expanded mesh
+        assert!(out.len() <= triangles.len() + vertices.len());
+
+        let mut provoked = vec![false; out.len()];
+        for (r, f) in remapped.iter().zip(flat.iter()) {
+            let r = [r.x, r.y, r.z];
+            let p = r[corner];
+            assert!(!provoked[p], "vertex {} provokes twice", p);
+            provoked[p] = true;
+            assert_eq!(out[p].normal, f.x.normal);
+
+            // same triangle, same winding, only rotated
+            let f = [f.x.pos, f.y.pos, f.z.pos];
+            let shift = (0..3).find(|&s| out[r[0]].pos == f[s]).unwrap();
+            for i in 0..3 {
+                assert_eq!(out[r[i]].pos, f[(shift + i) % 3]);
+            }
+        }
+    }
+}
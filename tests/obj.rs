@@ -0,0 +1,199 @@
+use std::ops::Range;
+
+use genmesh::generators::{Cube, IcoSphere, IndexedPolygon, Plane, SharedVertex};
+use genmesh::obj::{self, FaceRange, ObjWriter};
+use genmesh::{Polygon, Position, Quad, ReadError, TexCoord, Triangle, VertexAttributes};
+
+fn written<F: FnOnce(&mut ObjWriter<Vec<u8>>)>(f: F) -> String {
+    let mut writer = ObjWriter::new(Vec::new());
+    f(&mut writer);
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+#[test]
+fn obj_polygon_stream() {
+    let obj = written(|w| w.write_polygons(Cube::new()).unwrap());
+    let lines: Vec<&str> = obj.lines().collect();
+    assert_eq!(lines.iter().filter(|l| l.starts_with("v ")).count(), 24);
+    assert_eq!(lines.iter().filter(|l| l.starts_with("vn ")).count(), 24);
+    assert!(!obj.contains("vt "));
+
+    let faces: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|l| l.starts_with("f "))
+        .collect();
+    assert_eq!(faces.len(), 6);
+    // quads stay quads, and every vertex has its own normal
+    assert_eq!(faces[0], "f 1//1 2//2 3//3 4//4");
+    assert_eq!(faces[5], "f 21//21 22//22 23//23 24//24");
+    assert_eq!(lines[0], "v 1 1 -1");
+}
+
+#[derive(Clone, Copy)]
+struct Textured {
+    pos: Position,
+    uv: TexCoord,
+}
+
+impl VertexAttributes for Textured {
+    fn position(&self) -> Position {
+        self.pos
+    }
+
+    fn tex_coord(&self) -> Option<TexCoord> {
+        Some(self.uv)
+    }
+}
+
+#[test]
+fn obj_indexed_with_tex_coords() {
+    let vertices = [
+        Textured {
+            pos: [0., 0., 0.].into(),
+            uv: [0., 0.].into(),
+        },
+        Textured {
+            pos: [1., 0., 0.].into(),
+            uv: [1., 0.].into(),
+        },
+        Textured {
+            pos: [0., 1., 0.5].into(),
+            uv: [0., 1.].into(),
+        },
+    ];
+    let obj = written(|w| {
+        w.material("red").unwrap();
+        w.write_indexed(&vertices, &[Triangle::new(0, 1, 2), Triangle::new(2, 1, 0)])
+            .unwrap()
+    });
+    assert_eq!(
+        obj,
+        "usemtl red\n\
+         v 0 0 0\nvt 0 0\n\
+         v 1 0 0\nvt 1 0\n\
+         v 0 1 0.5\nvt 0 1\n\
+         f 1/1 2/2 3/3\n\
+         f 3/3 2/2 1/1\n"
+    );
+}
+
+#[test]
+fn obj_generator_groups() {
+    let obj = written(|w| {
+        w.write_generator("plane", &Plane::new()).unwrap();
+        w.write_generator("sphere", &IcoSphere::new()).unwrap();
+    });
+    let groups: Vec<&str> = obj.lines().filter(|l| l.starts_with("g ")).collect();
+    assert_eq!(groups, vec!["g plane", "g sphere"]);
+
+    // the sphere indexes past the 4 vertices of the plane
+    let sphere = &obj[obj.find("g sphere").unwrap()..];
+    let faces: Vec<&str> = sphere.lines().filter(|l| l.starts_with("f ")).collect();
+    assert_eq!(faces.len(), 20);
+    for f in faces {
+        for corner in f[2..].split(' ') {
+            let v: usize = corner.split("//").next().unwrap().parse().unwrap();
+            assert!((5..=16).contains(&v));
+        }
+    }
+}
+
+#[test]
+fn obj_round_trip() {
+    let obj = written(|w| w.write_generator("cube", &Cube::new()).unwrap());
+    let mesh = obj::read(obj.as_bytes()).unwrap();
+
+    let cube = Cube::new();
+    assert_eq!(mesh.vertices.len(), 24);
+    for (i, v) in mesh.vertices.iter().enumerate() {
+        let original = cube.shared_vertex(i);
+        assert_eq!(v.position, original.pos);
+        assert_eq!(v.normal, Some(original.normal));
+        assert_eq!(v.tex_coord, None);
+    }
+    let quads: Vec<Polygon<usize>> = cube.indexed_polygon_iter().map(Polygon::PolyQuad).collect();
+    assert_eq!(mesh.polygons, quads);
+    assert_eq!(mesh.position_polygons, quads);
+    assert_eq!(
+        mesh.groups,
+        vec![FaceRange {
+            name: "cube".into(),
+            polygons: 0..6
+        }]
+    );
+}
+
+#[test]
+fn obj_read_statements() {
+    let source = "\
+# two quads sharing an edge, with one normal per face
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 2 0 0
+v 2 1 0
+vt 0 0
+vt 1
+vn 0 0 1
+vn 0 0 -1
+g left
+usemtl red
+f 1//1 2//1 3//1 4//1
+g right
+f -5/2/2 -2/1/2 -1/1/2 -4/2/2 # relative indices
+usemtl blue
+f 1 2 5 6 3 4
+";
+    let mesh = obj::read(source.as_bytes()).unwrap();
+    assert_eq!(mesh.positions.len(), 6);
+    // the shared edge is split, since the normals differ
+    assert_eq!(mesh.vertices.len(), 4 + 4 + 6);
+    assert_eq!(mesh.vertices[4].tex_coord, Some([1., 0.].into()));
+    assert_eq!(mesh.vertices[4].normal, Some([0., 0., -1.].into()));
+    assert_eq!(mesh.polygons.len(), 2 + 4);
+    assert_eq!(
+        mesh.position_polygons[1],
+        Polygon::PolyQuad(Quad::new(1, 4, 5, 2))
+    );
+    // the hexagon is split into a fan
+    assert_eq!(
+        mesh.position_polygons[2..],
+        [
+            Polygon::PolyTri(Triangle::new(0, 1, 4)),
+            Polygon::PolyTri(Triangle::new(0, 4, 5)),
+            Polygon::PolyTri(Triangle::new(0, 5, 2)),
+            Polygon::PolyTri(Triangle::new(0, 2, 3)),
+        ]
+    );
+
+    let ranges = |r: &[FaceRange]| -> Vec<(String, Range<usize>)> {
+        r.iter()
+            .map(|r| (r.name.clone(), r.polygons.clone()))
+            .collect()
+    };
+    assert_eq!(
+        ranges(&mesh.groups),
+        vec![("left".into(), 0..1), ("right".into(), 1..6)]
+    );
+    assert_eq!(
+        ranges(&mesh.materials),
+        vec![("red".into(), 0..2), ("blue".into(), 2..6)]
+    );
+}
+
+#[test]
+fn obj_read_errors() {
+    let line_of = |source: &str| match obj::read(source.as_bytes()) {
+        Err(ReadError::Parse { line, .. }) => line,
+        other => panic!("expected a parse error, got {:?}", other),
+    };
+    assert_eq!(line_of("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n"), 4);
+    assert_eq!(line_of("v 0 0 0\nv 1 zero 0\n"), 2);
+    assert_eq!(line_of("v 0 0\n"), 1);
+    assert_eq!(line_of("v 0 0 0\nv 1 0 0\n\nf 1 2\n"), 4);
+    assert_eq!(line_of("v 0 0 0\nf 0 1 1\n"), 2);
+    assert_eq!(line_of("v 0 0 0\nvn 0 0 1\nf 1//2 1//1 1//1\n"), 3);
+}
@@ -0,0 +1,130 @@
+use genmesh::generators::{Cylinder, IndexedPolygon, SharedVertex, Torus};
+use genmesh::off::{self, Color};
+use genmesh::{MapVertex, Polygon, Position, Quad, ReadError, Triangle};
+
+#[test]
+fn off_round_trip() {
+    let torus = Torus::new(1., 0.25, 8, 6);
+    let vertices: Vec<Position> = torus.shared_vertex_iter().map(|v| v.pos).collect();
+    let quads: Vec<Quad<usize>> = torus.indexed_polygon_iter().collect();
+
+    let mut data = Vec::new();
+    off::write(&mut data, &vertices, &quads).unwrap();
+    let text = String::from_utf8(data.clone()).unwrap();
+    assert!(text.starts_with("OFF\n"));
+
+    let mesh = off::read(&data[..]).unwrap();
+    assert_eq!(mesh.positions, vertices);
+    let expected: Vec<Polygon<usize>> = quads.iter().map(|&q| q.into()).collect();
+    assert_eq!(mesh.polygons, expected);
+    assert!(mesh.vertex_colors.is_empty());
+    assert!(mesh.face_colors.is_empty());
+}
+
+#[test]
+fn coff_round_trip() {
+    let cylinder = Cylinder::new(6);
+    let vertices: Vec<Position> = cylinder.shared_vertex_iter().map(|v| v.pos).collect();
+    let polygons: Vec<Polygon<usize>> = cylinder.indexed_polygon_iter().collect();
+    let vertex_colors: Vec<Color> = vertices
+        .iter()
+        .map(|p| [(p.z + 1.) / 2., 0.5, 0.25, 1.])
+        .collect();
+    let face_colors: Vec<Color> = (0..polygons.len())
+        .map(|i| [0., 0., 1., i as f32 / polygons.len() as f32])
+        .collect();
+
+    let mut data = Vec::new();
+    off::write_colored(
+        &mut data,
+        &vertices,
+        &vertex_colors,
+        &polygons,
+        &face_colors,
+    )
+    .unwrap();
+    assert!(data.starts_with(b"COFF\n"));
+
+    let mesh = off::read(&data[..]).unwrap();
+    assert_eq!(mesh.positions, vertices);
+    assert_eq!(mesh.vertex_colors, vertex_colors);
+    assert_eq!(mesh.polygons, polygons);
+    assert_eq!(mesh.face_colors, face_colors);
+
+    // the color lists have to match the mesh
+    let err =
+        off::write_colored(Vec::new(), &vertices, &[], &polygons, &face_colors[1..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn off_foreign_file() {
+    let data = "\
+# a pentagon and a triangle, with integer colors
+OFF
+6 2 7
+
+0 0 0
+1 0 0
+1.5 1 0 # trailing comment
+0.5 2 0
+-0.5 1 0
+0 0 1
+5 0 1 2 3 4 255 0 0 128
+3 0 1 5 0 255 0
+";
+    let mesh = off::read(data.as_bytes()).unwrap();
+    assert_eq!(mesh.positions.len(), 6);
+    // the pentagon is split into a fan
+    let expected: Vec<Polygon<usize>> = vec![
+        Triangle::new(0, 1, 2).into(),
+        Triangle::new(0, 2, 3).into(),
+        Triangle::new(0, 3, 4).into(),
+        Triangle::new(0, 1, 5).into(),
+    ];
+    assert_eq!(mesh.polygons, expected);
+    let red = [1., 0., 0., 128. / 255.];
+    assert_eq!(mesh.face_colors, vec![red, red, red, [0., 1., 0., 1.]]);
+
+    // reading the triangles back as positions
+    let first = mesh.polygons[0].map_vertex(|i| mesh.positions[i]);
+    assert_eq!(
+        first,
+        Polygon::PolyTri(Triangle::new(
+            [0., 0., 0.].into(),
+            [1., 0., 0.].into(),
+            [1.5, 1., 0.].into()
+        ))
+    );
+
+    // a color with any float component is read entirely from 0 to 1
+    let data = "OFF 3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2 0.5 1 0\n";
+    let mesh = off::read(data.as_bytes()).unwrap();
+    assert_eq!(mesh.face_colors, vec![[0.5, 1., 0., 1.]]);
+}
+
+#[test]
+fn off_malformed() {
+    let line_of = |data: &str| match off::read(data.as_bytes()) {
+        Err(ReadError::Parse { line, .. }) => line,
+        other => panic!("expected a parse error, got {:?}", other),
+    };
+    assert_eq!(line_of("PLY\n"), 1);
+    assert_eq!(line_of("OFF\n3\n"), 2);
+    assert_eq!(line_of("OFF 3 1 0\n0 0 0\n1 0\n0 1 0\n3 0 1 2\n"), 3);
+    assert_eq!(line_of("OFF 3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n"), 5);
+    assert_eq!(line_of("OFF 3 1 0\n0 0 0\n1 0 0\n0 1 0\n2 0 1\n"), 5);
+    assert_eq!(
+        line_of("OFF 3 2 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2 1 0 0\n"),
+        5
+    );
+    assert_eq!(
+        line_of("OFF 3 2 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2 1 0 0\n3 0 2 1\n"),
+        6
+    );
+    assert_eq!(line_of("OFF 3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2 0.5\n"), 5);
+    assert_eq!(
+        line_of("OFF 3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2 256 0 0\n"),
+        5
+    );
+}
@@ -0,0 +1,153 @@
+use genmesh::generators::{IcoSphere, IndexedPolygon, SharedVertex, Torus};
+use genmesh::{
+    average_cache_miss_ratio, optimize_overdraw, optimize_vertex_cache, optimize_vertex_fetch,
+    Indexer, LruIndexer, MapToVertices, MapVertex, Triangle, Triangulate, Vertex, Vertices,
+};
+
+/// Builds a subdivided icosphere with its triangles in a scattered order.
+fn scrambled_sphere() -> (Vec<Vertex>, Vec<Triangle<usize>>) {
+    let sphere = IcoSphere::subdivide(3);
+    let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = sphere.indexed_polygon_iter().collect();
+    // 7 is coprime with the triangle count, so this is a permutation
+    let n = triangles.len();
+    let triangles = (0..n).map(|i| triangles[i * 7 % n]).collect();
+    (vertices, triangles)
+}
+
+fn sorted(triangles: &[Triangle<usize>]) -> Vec<(usize, usize, usize)> {
+    let mut v: Vec<_> = triangles.iter().map(|t| (t.x, t.y, t.z)).collect();
+    v.sort();
+    v
+}
+
+#[test]
+fn vertex_fetch_first_use_order() {
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut polygons: Vec<Triangle<usize>> = {
+        let mut indexer = LruIndexer::new(16, |_, v| vertices.push(v));
+        Torus::new(1., 0.5, 8, 6)
+            .triangulate()
+            .vertex(|v| indexer.index(v))
+            .collect()
+    };
+    // scramble the order in which the triangles reference the vertices
+    polygons.reverse();
+    let expected: Vec<_> = polygons
+        .iter()
+        .map(|t| t.map_vertex(|i| vertices[i]))
+        .collect();
+
+    let (reordered, remap) = optimize_vertex_fetch(&vertices, &mut polygons);
+    assert_eq!(reordered.len(), remap.len());
+
+    let mut next = 0;
+    for i in polygons.iter().copied().vertices() {
+        assert!(i <= next, "vertex {} is used before {}", next, i);
+        if i == next {
+            next += 1;
+        }
+    }
+    for (t, e) in polygons.iter().zip(expected.iter()) {
+        assert_eq!(&t.map_vertex(|i| reordered[i]), e);
+    }
+}
+
+#[test]
+fn vertex_fetch_drops_unused() {
+    // the torus emits one more shared vertex than its faces reference
+    let torus = Torus::new(1., 0.5, 8, 6);
+    let vertices: Vec<Vertex> = torus.shared_vertex_iter().collect();
+    let mut polygons: Vec<_> = torus.indexed_polygon_iter().collect();
+
+    let (reordered, remap) = optimize_vertex_fetch(&vertices, &mut polygons);
+    assert_eq!(vertices.len() - 1, reordered.len());
+    for (new, &old) in remap.iter().enumerate() {
+        assert_eq!(reordered[new], vertices[old]);
+    }
+}
+
+#[test]
+fn vertex_cache_reduces_misses() {
+    let (vertices, mut triangles) = scrambled_sphere();
+    let original = triangles.clone();
+    let before = average_cache_miss_ratio(&triangles, vertices.len(), 16);
+
+    optimize_vertex_cache(&mut triangles, vertices.len());
+    let after = average_cache_miss_ratio(&triangles, vertices.len(), 16);
+
+    assert_eq!(sorted(&original), sorted(&triangles));
+    assert!(after < 0.8, "ACMR {} is too high", after);
+    assert!(after < before);
+}
+
+#[test]
+fn overdraw_respects_threshold() {
+    let (vertices, mut triangles) = scrambled_sphere();
+    optimize_vertex_cache(&mut triangles, vertices.len());
+    let original = triangles.clone();
+    let before = average_cache_miss_ratio(&triangles, vertices.len(), 16);
+
+    optimize_overdraw(&vertices, &mut triangles, 1.05, |v| v.pos);
+    let after = average_cache_miss_ratio(&triangles, vertices.len(), 16);
+
+    assert_eq!(sorted(&original), sorted(&triangles));
+    assert!(
+        after <= before * 1.05,
+        "ACMR went from {} to {}",
+        before,
+        after
+    );
+}
+
+/// How much a triangle of a mesh centered on the origin faces outwards.
+fn facing(vertices: &[Vertex], t: &Triangle<usize>) -> f32 {
+    let [a, b, c] = [t.x, t.y, t.z].map(|i| {
+        let p = vertices[i].pos;
+        [p.x, p.y, p.z]
+    });
+    let (u, v) = (
+        [b[0] - a[0], b[1] - a[1], b[2] - a[2]],
+        [c[0] - a[0], c[1] - a[1], c[2] - a[2]],
+    );
+    let n = [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ];
+    (0..3).map(|k| (a[k] + b[k] + c[k]) / 3. * n[k]).sum()
+}
+
+#[test]
+fn overdraw_draws_outer_faces_first() {
+    // the inside of the ring faces the center of the torus
+    let torus = Torus::new(1., 0.5, 32, 16);
+    let vertices: Vec<Vertex> = torus.shared_vertex_iter().collect();
+    let mut triangles: Vec<Triangle<usize>> = torus.indexed_polygon_iter().triangulate().collect();
+    optimize_vertex_cache(&mut triangles, vertices.len());
+    // start from the worst order, with the inner faces first
+    triangles.reverse();
+    let original = triangles.clone();
+
+    let mean = |triangles: &[Triangle<usize>]| {
+        triangles.iter().map(|t| facing(&vertices, t)).sum::<f32>() / triangles.len() as f32
+    };
+    let half = triangles.len() / 2;
+    assert!(mean(&original[..half]) < mean(&original[half..]));
+
+    optimize_overdraw(&vertices, &mut triangles, 1.05, |v| v.pos);
+    assert_eq!(sorted(&original), sorted(&triangles));
+    assert!(mean(&triangles[..half]) > mean(&triangles[half..]));
+}
+
+#[test]
+fn overdraw_non_finite_positions() {
+    let (mut vertices, mut triangles) = scrambled_sphere();
+    optimize_vertex_cache(&mut triangles, vertices.len());
+    let original = triangles.clone();
+    vertices[0].pos.x = f32::NAN;
+    vertices[5].pos.z = f32::INFINITY;
+
+    optimize_overdraw(&vertices, &mut triangles, 1.05, |v| v.pos);
+    assert_eq!(sorted(&original), sorted(&triangles));
+}
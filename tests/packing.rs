@@ -0,0 +1,155 @@
+use genmesh::generators::{SharedVertex, SphereUv};
+use genmesh::{
+    Attribute, AttributeLayout, Format, Position, TexCoord, Vertex, VertexAttributes, VertexLayout,
+};
+
+fn f32_at(data: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+#[test]
+fn interleaved_layout() {
+    let layout = VertexLayout::interleaved(&[
+        (Attribute::Position, Format::Float32x4),
+        (Attribute::TexCoord, Format::Float32x2),
+        (Attribute::Normal, Format::Float32x3),
+    ]);
+    assert_eq!(layout.buffer_count(), 1);
+    assert_eq!(layout.stride(0), 36);
+    assert_eq!(
+        layout.attributes[2],
+        AttributeLayout {
+            attribute: Attribute::Normal,
+            format: Format::Float32x3,
+            buffer: 0,
+            offset: 24,
+            stride: 36,
+        }
+    );
+
+    let sphere = SphereUv::new(8, 4);
+    let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+    let buffers = layout.pack(&vertices).buffers;
+    assert_eq!(buffers.len(), 1);
+    let data = &buffers[0];
+    assert_eq!(data.len(), 36 * vertices.len());
+    for (i, v) in vertices.iter().enumerate() {
+        let at = |offset: usize| f32_at(data, i * 36 + offset);
+        assert_eq!(
+            [at(0), at(4), at(8), at(12)],
+            [v.pos.x, v.pos.y, v.pos.z, 1.]
+        );
+        // `Vertex` has no texture coordinates
+        assert_eq!([at(16), at(20)], [0., 0.]);
+        assert_eq!(
+            [at(24), at(28), at(32)],
+            [v.normal.x, v.normal.y, v.normal.z]
+        );
+    }
+}
+
+struct Textured {
+    pos: Position,
+    uv: TexCoord,
+}
+
+impl VertexAttributes for Textured {
+    fn position(&self) -> Position {
+        self.pos
+    }
+
+    fn tex_coord(&self) -> Option<TexCoord> {
+        Some(self.uv)
+    }
+}
+
+#[test]
+fn planar_layout() {
+    let layout = VertexLayout::planar(&[
+        (Attribute::TexCoord, Format::Float32x2),
+        (Attribute::Position, Format::Float32x2),
+        (Attribute::Tangent, Format::Float32),
+    ]);
+    assert_eq!(layout.buffer_count(), 3);
+    assert_eq!(
+        (layout.stride(0), layout.stride(1), layout.stride(2)),
+        (8, 8, 4)
+    );
+    assert_eq!(layout.stride(3), 0);
+    assert!(layout.attributes.iter().all(|a| a.offset == 0));
+
+    let vertices = vec![
+        Textured {
+            pos: [1., 2., 3.].into(),
+            uv: [0.25, 0.75].into(),
+        },
+        Textured {
+            pos: [4., 5., 6.].into(),
+            uv: [0.5, 1.].into(),
+        },
+    ];
+    let buffers = layout.pack(&vertices).buffers;
+    let floats = |data: &[u8]| -> Vec<f32> {
+        data.chunks(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    };
+    assert_eq!(floats(&buffers[0]), vec![0.25, 0.75, 0.5, 1.]);
+    // extra components are dropped
+    assert_eq!(floats(&buffers[1]), vec![1., 2., 4., 5.]);
+    assert_eq!(floats(&buffers[2]), vec![0., 0.]);
+}
+
+#[test]
+fn hand_built_layout() {
+    // positions and texture coordinates share the second of two buffers
+    let layout = VertexLayout {
+        attributes: vec![
+            AttributeLayout {
+                attribute: Attribute::TexCoord,
+                format: Format::Float32x2,
+                buffer: 1,
+                offset: 12,
+                stride: 20,
+            },
+            AttributeLayout {
+                attribute: Attribute::Position,
+                format: Format::Float32x3,
+                buffer: 1,
+                offset: 0,
+                stride: 20,
+            },
+        ],
+    };
+    let vertices = vec![Textured {
+        pos: [1., 2., 3.].into(),
+        uv: [0.25, 0.75].into(),
+    }];
+    let buffers = layout.pack(&vertices).buffers;
+    assert!(buffers[0].is_empty());
+    let data = &buffers[1];
+    assert_eq!(
+        (0..5).map(|i| f32_at(data, i * 4)).collect::<Vec<_>>(),
+        vec![1., 2., 3., 0.25, 0.75]
+    );
+}
+
+#[test]
+#[should_panic(expected = "does not fit in a stride of 16 bytes")]
+fn hand_built_layout_overflowing_stride() {
+    let layout = VertexLayout {
+        attributes: vec![AttributeLayout {
+            attribute: Attribute::Position,
+            format: Format::Float32x3,
+            buffer: 0,
+            offset: 8,
+            stride: 16,
+        }],
+    };
+    layout.pack(&[Position::from([0., 0., 0.])]);
+}
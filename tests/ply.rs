@@ -0,0 +1,280 @@
+use genmesh::generators::{IndexedPolygon, SharedVertex, Torus};
+use genmesh::ply::{self, PlyFormat, PlyType, PlyVertex};
+use genmesh::{Polygon, Position, Quad, ReadError, Triangle, Vertex};
+
+const FORMATS: [PlyFormat; 3] = [
+    PlyFormat::Ascii,
+    PlyFormat::BinaryLittleEndian,
+    PlyFormat::BinaryBigEndian,
+];
+
+fn torus() -> (Vec<Vertex>, Vec<Quad<usize>>) {
+    let torus = Torus::new(1., 0.25, 12, 6);
+    (
+        torus.shared_vertex_iter().collect(),
+        torus.indexed_polygon_iter().collect(),
+    )
+}
+
+fn written<V: PlyVertex, P: genmesh::EmitVertices<usize> + Clone>(
+    format: PlyFormat,
+    vertices: &[V],
+    polygons: &[P],
+) -> Vec<u8> {
+    let mut data = Vec::new();
+    ply::write(&mut data, format, vertices, polygons).unwrap();
+    data
+}
+
+#[test]
+fn ply_round_trip() {
+    let (vertices, quads) = torus();
+    let expected: Vec<Polygon<usize>> = quads.iter().map(|&q| Polygon::PolyQuad(q)).collect();
+    for &format in &FORMATS {
+        let data = written(format, &vertices, &quads);
+        let (read, polygons): (Vec<Vertex>, _) = ply::read(&data[..]).unwrap();
+        assert_eq!(read, vertices);
+        assert_eq!(polygons, expected);
+
+        // the positions alone can be read back as well
+        let (positions, _): (Vec<Position>, _) = ply::read(&data[..]).unwrap();
+        assert_eq!(positions[1], vertices[1].pos);
+    }
+
+    let ascii = String::from_utf8(written(PlyFormat::Ascii, &vertices, &quads)).unwrap();
+    assert!(ascii.starts_with("ply\nformat ascii 1.0\n"));
+    assert!(ascii.contains("element vertex 73\nproperty float x\n"));
+    assert!(ascii.contains("end_header\n"));
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Scanned {
+    pos: [f32; 3],
+    color: [u8; 3],
+    uv: [f32; 2],
+}
+
+impl PlyVertex for Scanned {
+    fn properties() -> Vec<(&'static str, PlyType)> {
+        vec![
+            ("x", PlyType::Float),
+            ("y", PlyType::Float),
+            ("z", PlyType::Float),
+            ("red", PlyType::UChar),
+            ("green", PlyType::UChar),
+            ("blue", PlyType::UChar),
+            ("s", PlyType::Float),
+            ("t", PlyType::Float),
+        ]
+    }
+
+    fn to_properties(&self, values: &mut [f64]) {
+        let [x, y, z] = self.pos;
+        let [r, g, b] = self.color;
+        let [s, t] = self.uv;
+        let all = [
+            x as f64, y as f64, z as f64, r as f64, g as f64, b as f64, s as f64, t as f64,
+        ];
+        values.copy_from_slice(&all);
+    }
+
+    fn from_properties<F: Fn(&str) -> Option<f64>>(get: F) -> Option<Self> {
+        Some(Scanned {
+            pos: [get("x")? as f32, get("y")? as f32, get("z")? as f32],
+            color: [get("red")? as u8, get("green")? as u8, get("blue")? as u8],
+            uv: [get("s")? as f32, get("t")? as f32],
+        })
+    }
+}
+
+#[test]
+fn ply_custom_properties() {
+    let vertices: Vec<Scanned> = (0..5)
+        .map(|i| Scanned {
+            pos: [i as f32 * 0.1, (i * i) as f32, -1.5],
+            color: [i * 50, 255 - i * 50, 7],
+            uv: [i as f32 / 4., 1. - i as f32 / 8.],
+        })
+        .collect();
+    let triangles = [Triangle::new(0, 1, 2), Triangle::new(2, 3, 4)];
+    for &format in &FORMATS {
+        let data = written(format, &vertices, &triangles);
+        let (read, polygons): (Vec<Scanned>, _) = ply::read(&data[..]).unwrap();
+        assert_eq!(read, vertices);
+        assert_eq!(polygons[1], Polygon::PolyTri(triangles[1]));
+    }
+
+    // a file without colors can not be read as scanned vertices
+    let data = written(
+        PlyFormat::Ascii,
+        &[Position::from([0., 0., 0.])],
+        &triangles[..0],
+    );
+    match ply::read::<_, Scanned>(&data[..]) {
+        Err(ReadError::Parse { line, .. }) => assert_eq!(line, 11),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+/// A vertex that hands its integer properties over as arbitrary floats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Levels([f64; 3]);
+
+impl PlyVertex for Levels {
+    fn properties() -> Vec<(&'static str, PlyType)> {
+        vec![
+            ("level", PlyType::UChar),
+            ("offset", PlyType::Short),
+            ("count", PlyType::UInt),
+        ]
+    }
+
+    fn to_properties(&self, values: &mut [f64]) {
+        values.copy_from_slice(&self.0);
+    }
+
+    fn from_properties<F: Fn(&str) -> Option<f64>>(get: F) -> Option<Self> {
+        Some(Levels([get("level")?, get("offset")?, get("count")?]))
+    }
+}
+
+#[test]
+fn ply_integer_properties_are_rounded_and_clamped() {
+    let vertices = [Levels([2.7, -1e6, -0.4]), Levels([300., 12.5, f64::NAN])];
+    let expected = vec![Levels([3., -32768., 0.]), Levels([255., 13., 0.])];
+    let triangles: [Triangle<usize>; 0] = [];
+    for &format in &FORMATS {
+        let data = written(format, &vertices, &triangles);
+        let (read, _): (Vec<Levels>, _) = ply::read(&data[..]).unwrap();
+        assert_eq!(read, expected, "{:?}", format);
+    }
+}
+
+#[test]
+fn ply_read_foreign_file() {
+    let source = "\
+ply
+format ascii 1.0
+comment from a scanner
+element vertex 5
+property double confidence
+property float x
+property float y
+property float z
+element face 2
+property uchar flags
+property list uchar int vertex_indices
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+0.5 0 0 0
+0.5 1 0 0
+0.5 1 1 0
+0.5 0 1 0
+0.5 0 2 0
+3 4 0 1 2 3
+0 3 3 2 4
+0 1
+";
+    let (vertices, polygons): (Vec<Position>, _) = ply::read(source.as_bytes()).unwrap();
+    assert_eq!(vertices[2], [1., 1., 0.].into());
+    assert_eq!(
+        polygons,
+        vec![
+            Polygon::PolyQuad(Quad::new(0, 1, 2, 3)),
+            Polygon::PolyTri(Triangle::new(3, 2, 4)),
+        ]
+    );
+}
+
+#[test]
+fn ply_malformed_headers() {
+    let cases = [
+        ("", 1),
+        ("plx\n", 1),
+        ("ply\nformat ascii 2.0\nend_header\n", 2),
+        ("ply\nformat ascii 1.0\nproperty float x\nend_header\n", 3),
+        ("ply\nformat ascii 1.0\nelement vertex -1\nend_header\n", 3),
+        (
+            "ply\nformat ascii 1.0\nelement vertex 1\nproperty vec3 x\nend_header\n",
+            4,
+        ),
+        (
+            "ply\nformat ascii 1.0\nelement face 1\nproperty list float int v\nend_header\n",
+            4,
+        ),
+        ("ply\nformat ascii 1.0\nelement vertex 1\nend_header\n", 4),
+        ("ply\nelement vertex 0\nend_header\n", 3),
+        ("ply\nformat ascii 1.0\nelement vertex 0\n", 4),
+        ("ply\nformat ascii 1.0\nwhatever\nend_header\n", 3),
+        (
+            "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nend_header\n",
+            5,
+        ),
+        (
+            "ply\nformat ascii 1.0\nelement vertex 1\nproperty int x\nend_header\n0.5\n",
+            6,
+        ),
+        (
+            "ply\nformat ascii 1.0\nelement vertex 1\nproperty int x\nend_header\n1 2\n",
+            6,
+        ),
+    ];
+    for &(source, expected) in &cases {
+        match ply::read::<_, Position>(source.as_bytes()) {
+            Err(ReadError::Parse { line, .. }) => assert_eq!(line, expected, "{:?}", source),
+            other => panic!("expected a parse error for {:?}, got {:?}", source, other),
+        }
+    }
+
+    // faces report the line they are on, which a blank line moves down
+    let header = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\n\
+                  property float z\nelement face 2\nproperty list uchar int vertex_indices\n\
+                  end_header\n0 0 0\n\n";
+    for &(faces, expected) in &[
+        // out of range vertex indices
+        ("3 0 0 1\n3 0 0 0\n", 12),
+        // too few vertices
+        ("3 0 0 0\n2 0 0\n", 13),
+    ] {
+        match ply::read::<_, Position>(format!("{}{}", header, faces).as_bytes()) {
+            Err(ReadError::Parse { line, .. }) => assert_eq!(line, expected, "{:?}", faces),
+            other => panic!("expected a parse error for {:?}, got {:?}", faces, other),
+        }
+    }
+}
+
+#[test]
+fn ply_fuzzed_input_does_not_panic() {
+    let (vertices, quads) = torus();
+    let mut seed = 0x2545_f491_u32;
+    let mut random = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as usize
+    };
+
+    for &format in &FORMATS {
+        let data = written(format, &vertices[..8], &quads[..2]);
+        // every truncation of the file
+        for len in 0..data.len() {
+            let _ = ply::read::<_, Vertex>(&data[..len]);
+        }
+        // random byte changes, mostly in the header
+        for _ in 0..2000 {
+            let mut data = data.clone();
+            for _ in 0..1 + random() % 4 {
+                let at = random() % data.len().min(300);
+                data[at] = match random() % 4 {
+                    0 => b'9',
+                    1 => b' ',
+                    2 => b'\n',
+                    _ => random() as u8,
+                };
+            }
+            let _ = ply::read::<_, Vertex>(&data[..]);
+        }
+    }
+}
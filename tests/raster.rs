@@ -0,0 +1,134 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use genmesh::generators::{Circle, Cone, Cube, Cylinder, IcoSphere, Plane, SphereUv, Torus};
+use genmesh::raster::{Image, Rasterizer};
+use genmesh::{Camera, EmitTriangles, Position, Triangle, Vertex};
+
+// set GENMESH_BLESS=1 to write the rendered images over the golden ones
+fn check_golden<P>(name: &str, polygons: impl IntoIterator<Item = P>)
+where
+    P: EmitTriangles<Vertex = Vertex>,
+{
+    let camera = Camera::new([2.5, -4., 3.].into(), [0., 0., 0.].into());
+    let image = Rasterizer::new(64, 64, camera)
+        .ambient(0.1)
+        .cull_back_faces(true)
+        .render(polygons);
+
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    if std::env::var_os("GENMESH_BLESS").is_some() {
+        image
+            .write_pgm(BufWriter::new(File::create(&path).unwrap()))
+            .unwrap();
+        return;
+    }
+
+    let golden = Image::read_pnm(File::open(&path).unwrap()).unwrap();
+    // allow for rounding differences along the edges of triangles
+    let differences = image.count_differences(&golden, 2);
+    assert!(
+        differences <= 8,
+        "{} differs from the golden image in {} pixels",
+        name,
+        differences
+    );
+}
+
+#[test]
+fn golden_generators() {
+    check_golden("circle.pgm", Circle::new(16));
+    check_golden("cone.pgm", Cone::new(16));
+    check_golden("cube.pgm", Cube::new());
+    check_golden("cylinder.pgm", Cylinder::subdivide(16, 2));
+    check_golden("icosphere.pgm", IcoSphere::subdivide(2));
+    check_golden("plane.pgm", Plane::subdivide(2, 2));
+    check_golden("sphere_uv.pgm", SphereUv::new(16, 8));
+    check_golden("torus.pgm", Torus::new(1., 0.4, 16, 8));
+}
+
+#[test]
+fn z_buffer_keeps_the_nearest() {
+    let camera = Camera::new([0., 0., 5.].into(), [0., 0., 0.].into()).up([0., 1., 0.].into());
+    let quad = |z: f32, shade: f32| {
+        // a big triangle facing the camera, lit at an angle to tell it apart
+        let normal = Position::from([0., (1. - shade * shade).sqrt(), shade]);
+        let v = |x: f32, y: f32| Vertex {
+            pos: [x, y, z].into(),
+            normal,
+        };
+        Triangle::new(v(-4., -4.), v(4., -4.), v(0., 4.))
+    };
+
+    let rasterizer = Rasterizer::new(8, 8, camera).light(Some([0., 0., 1.].into()));
+    let near_first = rasterizer.render(vec![quad(1., 0.6), quad(0., 0.8)]);
+    let far_first = rasterizer.render(vec![quad(0., 0.8), quad(1., 0.6)]);
+    assert_eq!(near_first, far_first);
+    assert_eq!(near_first.pixel(4, 4), [153, 153, 153]);
+}
+
+#[test]
+fn lambert_shading() {
+    let camera = Camera::new([0., 0., 5.].into(), [0., 0., 0.].into()).up([0., 1., 0.].into());
+    let flat = |normal: [f32; 3]| {
+        let v = |x: f32, y: f32| Vertex {
+            pos: [x, y, 0.].into(),
+            normal: normal.into(),
+        };
+        vec![Triangle::new(v(-4., -4.), v(4., -4.), v(0., 4.))]
+    };
+
+    let rasterizer = Rasterizer::new(4, 4, camera)
+        .color([1., 0.5, 0.])
+        .background([0., 0., 1.])
+        .light(Some([0., 0., 1.].into()));
+    assert_eq!(
+        rasterizer.render(flat([0., 0., 1.])).pixel(2, 2),
+        [255, 128, 0]
+    );
+    let half = rasterizer.render(flat([0., 3f32.sqrt() / 2., 0.5]));
+    assert_eq!(half.pixel(2, 2), [128, 64, 0]);
+    // facing away from the light, with some ambient light
+    let dark = rasterizer.ambient(0.2).render(flat([0., 0., -1.]));
+    assert_eq!(dark.pixel(2, 2), [51, 26, 0]);
+
+    // back faces are dropped, leaving the background
+    let back = rasterizer.cull_back_faces(true).render(
+        flat([0., 0., 1.])
+            .into_iter()
+            .map(|t| Triangle::new(t.x, t.z, t.y)),
+    );
+    assert_eq!(back.pixel(2, 2), [0, 0, 255]);
+}
+
+#[test]
+fn pnm_round_trip() {
+    let camera = Camera::new([3., -4., 2.].into(), [0., 0., 0.].into());
+    let image = Rasterizer::new(24, 16, camera)
+        .color([0.9, 0.3, 0.1])
+        .render(Torus::new(1., 0.4, 12, 6));
+
+    let mut ppm = Vec::new();
+    image.write_ppm(&mut ppm).unwrap();
+    assert!(ppm.starts_with(b"P6\n24 16\n255\n"));
+    assert_eq!(Image::read_pnm(&ppm[..]).unwrap(), image);
+
+    let mut pgm = Vec::new();
+    image.write_pgm(&mut pgm).unwrap();
+    let gray = Image::read_pnm(&pgm[..]).unwrap();
+    assert_eq!((gray.width(), gray.height()), (24, 16));
+    assert!(gray.pixels().iter().all(|p| p[0] == p[1] && p[1] == p[2]));
+    assert_eq!(gray.count_differences(&image, 255), 0);
+    assert!(gray.count_differences(&image, 0) > 0);
+
+    // comments are allowed in the header
+    let commented = b"P5\n# a comment\n2 1\n255\n\x00\xff";
+    let image = Image::read_pnm(&commented[..]).unwrap();
+    assert_eq!(image.pixels(), &[[0, 0, 0], [255, 255, 255]]);
+    assert!(Image::read_pnm(&b"P5\n2 1\n255\n\x00"[..]).is_err());
+    assert!(Image::read_pnm(&b"P3\n1 1\n255\n0 0 0"[..]).is_err());
+    assert!(Image::read_pnm(&b"P5\n99999999999 99999999999 255\n"[..]).is_err());
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use genmesh::generators::{IcoSphere, IndexedPolygon, Plane, SharedVertex};
+use genmesh::{EmitLines, Line, MapVertex, Simplifier, Triangle, Triangulate, Vertex};
+
+fn key(v: &Vertex) -> [i32; 3] {
+    [v.pos.x, v.pos.y, v.pos.z].map(|x| (x * 1000.).round() as i32)
+}
+
+/// Counts how many triangles use each edge, with the vertices welded
+/// by position and the edge direction ignored.
+fn edge_use(vertices: &[Vertex], triangles: &[Triangle<usize>]) -> HashMap<Line<[i32; 3]>, usize> {
+    let mut edges = HashMap::new();
+    for t in triangles {
+        t.emit_lines(|Line { x, y }| {
+            let (x, y) = (key(&vertices[x]), key(&vertices[y]));
+            let line = Line::new(x.min(y), x.max(y));
+            *edges.entry(line).or_insert(0) += 1;
+        });
+    }
+    edges
+}
+
+#[test]
+fn simplify_sphere_stays_closed() {
+    let sphere = IcoSphere::subdivide(4);
+    let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = sphere.indexed_polygon_iter().collect();
+
+    let (simplified, error) = Simplifier::new(500).simplify(&vertices, &triangles, |v| v.pos);
+    assert!(simplified.len() <= 500 && simplified.len() > 400);
+    assert!(error > 0. && error < 0.05, "error {} out of range", error);
+    assert!(edge_use(&vertices, &simplified).values().all(|&n| n == 2));
+}
+
+#[test]
+fn simplify_max_error() {
+    let sphere = IcoSphere::subdivide(2);
+    let vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = sphere.indexed_polygon_iter().collect();
+
+    let (simplified, error) =
+        Simplifier::new(0)
+            .max_error(1e-4)
+            .simplify(&vertices, &triangles, |v| v.pos);
+    assert_eq!(simplified, triangles);
+    assert_eq!(error, 0.);
+}
+
+#[test]
+fn simplify_plane_keeps_boundary() {
+    let plane = Plane::subdivide(10, 10);
+    let vertices: Vec<Vertex> = plane.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = plane.indexed_polygon_iter().triangulate().collect();
+
+    let boundary = |triangles: &[Triangle<usize>]| {
+        let mut b: Vec<_> = edge_use(&vertices, triangles)
+            .into_iter()
+            .filter(|&(_, n)| n == 1)
+            .flat_map(|(l, _)| vec![l.x, l.y])
+            .collect();
+        b.sort();
+        b.dedup();
+        b
+    };
+
+    let (simplified, error) = Simplifier::new(0).simplify(&vertices, &triangles, |v| v.pos);
+    // every interior vertex of a flat plane can go without any error
+    assert_eq!(error, 0.);
+    assert!(simplified.len() < triangles.len() / 2);
+    assert_eq!(boundary(&triangles), boundary(&simplified));
+
+    let (unlocked, _) =
+        Simplifier::new(0)
+            .lock_boundary(false)
+            .simplify(&vertices, &triangles, |v| v.pos);
+    assert!(unlocked.len() < simplified.len());
+    assert!(edge_use(&vertices, &unlocked).values().all(|&n| n <= 2));
+}
+
+#[test]
+fn simplify_preserve_seams() {
+    // a plane with its middle column of vertices split in two, as if the
+    // two halves used different texture coordinates
+    let plane = Plane::subdivide(6, 6);
+    let mut vertices: Vec<Vertex> = plane.shared_vertex_iter().collect();
+    let seam: Vec<usize> = (0..7).map(|y| y * 7 + 3).collect();
+    let copies: HashMap<usize, usize> = seam
+        .iter()
+        .map(|&v| {
+            vertices.push(vertices[v]);
+            (v, vertices.len() - 1)
+        })
+        .collect();
+    let triangles: Vec<Triangle<usize>> = plane
+        .indexed_polygon_iter()
+        .enumerate()
+        .map(|(i, q)| {
+            if i % 6 >= 3 {
+                q.map_vertex(|v| *copies.get(&v).unwrap_or(&v))
+            } else {
+                q
+            }
+        })
+        .triangulate()
+        .collect();
+
+    let seam_points = |triangles: &[Triangle<usize>]| {
+        let mut points: Vec<_> = triangles
+            .iter()
+            .flat_map(|t| vec![t.x, t.y, t.z])
+            .filter(|v| seam.contains(v))
+            .map(|v| key(&vertices[v]))
+            .collect();
+        points.sort();
+        points.dedup();
+        points.len()
+    };
+
+    let (simplified, _) =
+        Simplifier::new(0)
+            .preserve_seams(true)
+            .simplify(&vertices, &triangles, |v| v.pos);
+    assert_eq!(seam_points(&simplified), 7);
+    assert!(edge_use(&vertices, &simplified).values().all(|&n| n <= 2));
+
+    let (simplified, _) = Simplifier::new(0).simplify(&vertices, &triangles, |v| v.pos);
+    assert!(seam_points(&simplified) < 7);
+    assert!(edge_use(&vertices, &simplified).values().all(|&n| n <= 2));
+}
+
+#[test]
+fn simplify_non_finite_positions() {
+    let sphere = IcoSphere::subdivide(2);
+    let mut vertices: Vec<Vertex> = sphere.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<usize>> = sphere.indexed_polygon_iter().collect();
+    vertices[0].pos.x = f32::NAN;
+    vertices[1].pos.y = f32::INFINITY;
+
+    let (simplified, _) =
+        Simplifier::new(triangles.len() / 4).simplify(&vertices, &triangles, |v| v.pos);
+    assert!(simplified.len() < triangles.len());
+}
+
+#[test]
+fn lod_chain_torus() {
+    use genmesh::generators::Torus;
+    use genmesh::LodChain;
+
+    let torus = Torus::new(1., 0.25, 32, 16);
+    let chain = LodChain::new(
+        &torus,
+        Simplifier::default(),
+        &[1., 0.5, 0.25, 0.125],
+        |v| v.pos,
+    );
+
+    assert_eq!(chain.vertices.len(), torus.shared_vertex_count());
+    assert_eq!(chain.levels.len(), 4);
+    assert_eq!(chain.level(0).len(), 32 * 16 * 2);
+    assert_eq!(chain.levels[0].error, 0.);
+
+    for (i, pair) in chain.levels.windows(2).enumerate() {
+        assert_eq!(pair[0].triangles.end, pair[1].triangles.start);
+        assert!(pair[0].error <= pair[1].error);
+        let target = (32 * 16 * 2) >> (i + 1);
+        assert!(chain.level(i + 1).len() <= target);
+        // each level stays a closed surface over the shared vertices
+        assert!(edge_use(&chain.vertices, chain.level(i + 1))
+            .values()
+            .all(|&n| n == 2));
+    }
+}
+
+#[test]
+fn lod_chain_uses_simplifier_options() {
+    use genmesh::LodChain;
+
+    // a plane only keeps its boundary when the simplifier locks it
+    let plane = Plane::subdivide(8, 8);
+    let ratios = [1., 0.];
+    let locked = LodChain::new(&plane, Simplifier::default(), &ratios, |v| v.pos);
+    let unlocked = LodChain::new(
+        &plane,
+        Simplifier::default().lock_boundary(false),
+        &ratios,
+        |v| v.pos,
+    );
+    assert!(unlocked.level(1).len() < locked.level(1).len());
+
+    // the template's error bound applies to every level
+    let capped = LodChain::new(&plane, Simplifier::default().max_error(0.), &ratios, |v| {
+        v.pos
+    });
+    assert_eq!(capped.levels[1].error, 0.);
+}
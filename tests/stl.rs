@@ -0,0 +1,84 @@
+use genmesh::generators::{Cube, Cylinder, IcoSphere, IndexedPolygon, SharedVertex};
+use genmesh::{stl, LruIndexer, MapToVertices, ReadError, Triangle, Triangulate};
+
+#[test]
+fn stl_ascii_round_trip() {
+    let mut ascii = Vec::new();
+    stl::write_ascii(&mut ascii, "cylinder", Cylinder::subdivide(8, 2)).unwrap();
+    let text = String::from_utf8(ascii.clone()).unwrap();
+    assert!(text.starts_with("solid cylinder\n"));
+    assert!(text.ends_with("endsolid cylinder\n"));
+
+    let expected: Vec<_> = Cylinder::subdivide(8, 2)
+        .triangulate()
+        .vertex(|v| v.pos)
+        .collect();
+    assert_eq!(stl::read(&ascii[..]).unwrap(), expected);
+}
+
+#[test]
+fn stl_binary_round_trip() {
+    let mut binary = Vec::new();
+    stl::write_binary(&mut binary, Cube::new()).unwrap();
+    assert_eq!(binary.len(), 84 + 50 * 12);
+    assert_eq!(&binary[80..84], &12u32.to_le_bytes());
+    // the facet normal of the first face of the cube points along +x
+    let normal: Vec<f32> = binary[84..96]
+        .chunks(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    assert_eq!(normal, vec![1., 0., 0.]);
+
+    let expected: Vec<_> = Cube::new().triangulate().vertex(|v| v.pos).collect();
+    assert_eq!(stl::read(&binary[..]).unwrap(), expected);
+}
+
+#[test]
+fn stl_read_indexed_welds() {
+    let sphere = IcoSphere::subdivide(1);
+    let mut binary = Vec::new();
+    stl::write_binary(
+        &mut binary,
+        sphere
+            .indexed_polygon_iter()
+            .vertex(|i| sphere.shared_vertex(i)),
+    )
+    .unwrap();
+
+    let mut positions = Vec::new();
+    let triangles = {
+        let mut indexer = LruIndexer::new(64, |_, p| positions.push(p));
+        stl::read_indexed(&binary[..], &mut indexer).unwrap()
+    };
+    assert_eq!(positions.len(), sphere.shared_vertex_count());
+    for (t, original) in triangles.iter().zip(sphere.indexed_polygon_iter()) {
+        assert_eq!(positions[t.x], sphere.shared_vertex(original.x).pos);
+        assert_eq!(positions[t.y], sphere.shared_vertex(original.y).pos);
+        assert_eq!(positions[t.z], sphere.shared_vertex(original.z).pos);
+    }
+}
+
+#[test]
+fn stl_read_errors() {
+    let line_of = |data: &[u8]| match stl::read(data) {
+        Err(ReadError::Parse { line, .. }) => line,
+        other => panic!("expected a parse error, got {:?}", other),
+    };
+
+    let mut binary = Vec::new();
+    stl::write_binary(&mut binary, Cube::new()).unwrap();
+    assert_eq!(line_of(&binary[..binary.len() - 10]), 0);
+
+    let ascii = "solid a\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 oops\n";
+    assert_eq!(line_of(ascii.as_bytes()), 5);
+    let ascii = "solid a\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nendloop\n";
+    assert_eq!(line_of(ascii.as_bytes()), 6);
+    let ascii = "solid a\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\n";
+    assert_eq!(line_of(ascii.as_bytes()), 4);
+
+    let empty: Vec<Triangle<_>> = Vec::new();
+    assert_eq!(
+        stl::read("solid empty\nendsolid empty\n".as_bytes()).unwrap(),
+        empty
+    );
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use genmesh::generators::{Cube, IcoSphere, IndexedPolygon, Plane, SharedVertex, SphereUv};
+use genmesh::{
+    catmull_clark, loop_subdivide, midpoint_subdivide, EmitLines, Indexer, Line, LruIndexer,
+    MapToVertices, Neighbors, Polygon, Quad, Triangle, Triangulate, Vertex,
+};
+
+/// A vertex type that genmesh knows nothing about.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Colored {
+    pos: [f32; 3],
+    color: [f32; 3],
+}
+
+fn blend(weights: &[(&Colored, f32)]) -> Colored {
+    let mut out = Colored {
+        pos: [0.; 3],
+        color: [0.; 3],
+    };
+    for &(v, w) in weights {
+        for i in 0..3 {
+            out.pos[i] += v.pos[i] * w;
+            out.color[i] += v.color[i] * w;
+        }
+    }
+    out
+}
+
+fn colored(v: Vertex) -> Colored {
+    Colored {
+        pos: [v.pos.x, v.pos.y, v.pos.z],
+        color: [v.pos.x.abs(), v.pos.y.abs(), v.pos.z.abs()],
+    }
+}
+
+fn is_closed<P: EmitLines<Vertex = usize> + Copy>(polygons: &[P]) -> bool {
+    let mut edges = HashMap::new();
+    for p in polygons {
+        p.emit_lines(|Line { x, y }| {
+            *edges.entry((x.min(y), x.max(y))).or_insert(0) += 1;
+        });
+    }
+    edges.values().all(|&n| n == 2)
+}
+
+#[test]
+fn loop_icosphere() {
+    let sphere = IcoSphere::new();
+    let mesh = Neighbors::new(
+        sphere.shared_vertex_iter().map(colored).collect(),
+        sphere.indexed_polygon_iter().collect(),
+    );
+
+    let (vertices, triangles) = loop_subdivide(&mesh.vertices, &mesh.polygons, blend);
+    assert_eq!(vertices.len(), 12 + 30);
+    assert_eq!(triangles.len(), 20 * 4);
+    assert!(is_closed(&triangles));
+
+    // Loop subdivision approximates, so the unit sphere shrinks a bit
+    for v in &vertices {
+        let r = (v.pos[0] * v.pos[0] + v.pos[1] * v.pos[1] + v.pos[2] * v.pos[2]).sqrt();
+        assert!(r > 0.7 && r < 1.0, "radius {}", r);
+        assert!(v.color.iter().all(|&c| (0. ..=1.).contains(&c)));
+    }
+
+    let (vertices, triangles) = loop_subdivide(&vertices, &triangles, blend);
+    assert_eq!(vertices.len(), 42 + 120);
+    assert_eq!(triangles.len(), 80 * 4);
+    assert!(is_closed(&triangles));
+}
+
+#[test]
+fn loop_plane_boundary() {
+    let plane = Plane::subdivide(2, 2);
+    let vertices: Vec<Colored> = plane.shared_vertex_iter().map(colored).collect();
+    let triangles: Vec<Triangle<usize>> = plane.indexed_polygon_iter().triangulate().collect();
+
+    let (out, faces) = loop_subdivide(&vertices, &triangles, blend);
+    assert_eq!(faces.len(), triangles.len() * 4);
+    // the source vertices keep their indices
+    assert_eq!(out[4].pos, [0., 0., 0.]);
+    for v in &out {
+        assert_eq!(v.pos[2], 0.);
+    }
+    // the boundary midpoint of the bottom edge only moves along the edge
+    assert_eq!(out[1].pos, [0., -1., 0.]);
+    // and the corners are pulled in by their boundary neighbors
+    assert_eq!(out[0].pos, [-0.875, -0.875, 0.]);
+}
+
+/// Welds the cube into 8 shared corners, dropping the per-face normals.
+fn welded_cube() -> (Vec<Colored>, Vec<Quad<usize>>) {
+    let mut vertices = Vec::new();
+    let quads = {
+        let mut indexer = LruIndexer::new(8, |_, v| vertices.push(v));
+        Cube::new()
+            .vertex(colored)
+            .vertex(|v| indexer.index(v))
+            .collect()
+    };
+    (vertices, quads)
+}
+
+#[test]
+fn catmull_clark_cube() {
+    let (vertices, quads) = welded_cube();
+    assert_eq!(vertices.len(), 8);
+
+    let (vertices, quads, creases) = catmull_clark(&vertices, &quads, &HashMap::new(), blend);
+    assert_eq!((vertices.len(), quads.len()), (8 + 6 + 12, 24));
+    assert!(creases.is_empty());
+    assert!(is_closed(&quads));
+    // the classic first step moves the corners to 5/9
+    for v in &vertices[..8] {
+        assert!(v.pos.iter().all(|&c| (c.abs() - 5. / 9.).abs() < 1e-6));
+    }
+
+    let (vertices, quads, _) = catmull_clark(&vertices, &quads, &creases, blend);
+    assert_eq!((vertices.len(), quads.len()), (26 + 24 + 48, 96));
+    assert!(is_closed(&quads));
+    // the result stays symmetric around every axis
+    for v in &vertices {
+        for axis in 0..3 {
+            let mut mirrored = v.pos;
+            mirrored[axis] = -mirrored[axis];
+            assert!(vertices.iter().any(|u| u
+                .pos
+                .iter()
+                .zip(mirrored.iter())
+                .all(|(a, b)| (a - b).abs() < 1e-6)));
+        }
+    }
+}
+
+#[test]
+fn catmull_clark_creases() {
+    let (vertices, quads) = welded_cube();
+    let mut creases = HashMap::new();
+    for q in &quads {
+        q.emit_lines(|l| {
+            creases.insert(l, 2.);
+        });
+    }
+
+    // with every edge sharp the cube keeps its shape for two levels
+    let (vertices, quads, creases) = catmull_clark(&vertices, &quads, &creases, blend);
+    assert_eq!(creases.len(), 12 * 2);
+    let (vertices, quads, creases) = catmull_clark(&vertices, &quads, &creases, blend);
+    assert!(creases.is_empty());
+    assert!(is_closed(&quads));
+    for v in &vertices {
+        let max = v.pos.iter().fold(0f32, |m, c| m.max(c.abs()));
+        assert!((max - 1.).abs() < 1e-6, "{:?} is off the cube", v.pos);
+    }
+}
+
+#[test]
+fn catmull_clark_mixed() {
+    // the sphere has triangles around its poles and quads elsewhere
+    let sphere = SphereUv::new(8, 4);
+    let vertices: Vec<Colored> = sphere.shared_vertex_iter().map(colored).collect();
+    let polygons: Vec<Polygon<usize>> = sphere.indexed_polygon_iter().collect();
+
+    let (_, quads, _) = catmull_clark(&vertices, &polygons, &HashMap::new(), blend);
+    assert_eq!(quads.len(), 16 * 3 + 16 * 4);
+    assert!(is_closed(&quads));
+}
+
+#[test]
+fn midpoint_cube_onto_sphere() {
+    let (vertices, quads) = welded_cube();
+    let mut project = |mut v: Colored| {
+        let r = v.pos.iter().map(|c| c * c).sum::<f32>().sqrt();
+        v.pos.iter_mut().for_each(|c| *c /= r);
+        v
+    };
+
+    let (out, split) = midpoint_subdivide(&vertices, &quads, blend, Some(&mut project));
+    assert_eq!(out.len(), 8 + 12 + 6);
+    assert_eq!(split.len(), 24);
+    assert!(is_closed(&split));
+    // the source vertices stay where they were
+    assert_eq!(&out[..8], &vertices[..]);
+    for v in &out[8..] {
+        let r = v.pos.iter().map(|c| c * c).sum::<f32>().sqrt();
+        assert!((r - 1.).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn midpoint_mixed() {
+    let sphere = SphereUv::new(8, 4);
+    let vertices: Vec<Colored> = sphere.shared_vertex_iter().map(colored).collect();
+    let polygons: Vec<Polygon<usize>> = sphere.indexed_polygon_iter().collect();
+
+    let (out, split) = midpoint_subdivide(&vertices, &polygons, blend, None);
+    assert_eq!(split.len(), polygons.len() * 4);
+    assert!(is_closed(&split));
+    // one vertex per edge and one per quad
+    assert_eq!(out.len(), vertices.len() + 8 * 7 + 16);
+    for (before, after) in polygons.iter().zip(split.chunks(4)) {
+        for p in after {
+            match (before, p) {
+                (Polygon::PolyTri(_), Polygon::PolyTri(_)) => {}
+                (Polygon::PolyQuad(_), Polygon::PolyQuad(_)) => {}
+                _ => panic!("{:?} was split into {:?}", before, p),
+            }
+        }
+    }
+}
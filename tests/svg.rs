@@ -0,0 +1,146 @@
+use genmesh::generators::{Cube, IndexedPolygon, Plane, SharedVertex, SphereUv};
+use genmesh::svg::SvgRenderer;
+use genmesh::{Camera, Lines, Projection};
+
+fn count(svg: &str, element: &str) -> usize {
+    svg.matches(element).count()
+}
+
+fn render_cube(renderer: &SvgRenderer) -> String {
+    let cube = Cube::new();
+    let vertices: Vec<_> = cube.shared_vertex_iter().collect();
+    let quads: Vec<_> = cube.indexed_polygon_iter().collect();
+    let mut out = Vec::new();
+    renderer.write_indexed(&mut out, &vertices, &quads).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn camera_projection() {
+    let camera = Camera::new([0., -4., 0.].into(), [0., 0., 0.].into());
+    // the top of a 45 degree view at the distance of the target
+    let top = (std::f32::consts::FRAC_PI_8).tan() * 4.;
+    let p = camera.project([0., 0., top].into(), 1.).unwrap();
+    assert!((p.y - 1.).abs() < 1e-5 && p.x.abs() < 1e-5);
+    // a wider screen squeezes x
+    let p = camera.project([top, 0., 0.].into(), 2.).unwrap();
+    assert!((p.x - 0.5).abs() < 1e-5);
+    // behind the eye
+    assert_eq!(camera.project([0., -5., 0.].into(), 1.), None);
+
+    let camera = camera.projection(Projection::Orthographic { height: 4. });
+    let p = camera.project([2., 10., -2.].into(), 1.).unwrap();
+    assert_eq!((p.x, p.y, p.z), (1., -1., 14.));
+}
+
+#[test]
+fn svg_wireframe_from_lines() {
+    let camera = Camera::new([3., -4., 2.].into(), [0., 0., 0.].into());
+    let mut out = Vec::new();
+    SvgRenderer::new(320, 240, camera)
+        .write_lines(&mut out, Cube::new().lines())
+        .unwrap();
+    let svg = String::from_utf8(out).unwrap();
+    assert!(svg.starts_with(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"320\" height=\"240\" viewBox=\"0 0 320 240\">\n"
+    ));
+    assert!(svg.ends_with("</g>\n</svg>\n"));
+    // every quad has its own four lines
+    assert_eq!(count(&svg, "<line "), 24);
+
+    // the target is in the middle of the image
+    let mut out = Vec::new();
+    SvgRenderer::new(320, 240, camera)
+        .write_lines(
+            &mut out,
+            vec![genmesh::Line::new(
+                genmesh::Position::from([0., 0., 0.]),
+                genmesh::Position::from([0., 0., 0.]),
+            )],
+        )
+        .unwrap();
+    let svg = String::from_utf8(out).unwrap();
+    assert!(svg.contains("<line x1=\"160.00\" y1=\"120.00\" x2=\"160.00\" y2=\"120.00\"/>"));
+
+    // the stroke can not break out of its attribute
+    let mut out = Vec::new();
+    SvgRenderer::new(320, 240, camera)
+        .stroke(Some("\"/><script>a & b</script>"))
+        .write_lines(&mut out, Cube::new().lines())
+        .unwrap();
+    let svg = String::from_utf8(out).unwrap();
+    assert!(svg.contains("stroke=\"&quot;/&gt;&lt;script&gt;a &amp; b&lt;/script&gt;\""));
+    assert!(!svg.contains("<script>"));
+}
+
+#[test]
+fn svg_indexed_culling() {
+    let camera = Camera::new([3., -4., 2.].into(), [0., 0., 0.].into());
+    let renderer = SvgRenderer::new(200, 200, camera);
+
+    // the cube has its own vertices on every face
+    let all = render_cube(&renderer);
+    assert_eq!(count(&all, "<line "), 24);
+    assert_eq!(count(&all, "<polygon "), 0);
+
+    // three faces are seen from a corner
+    let front = render_cube(&renderer.clone().cull_back_faces(true));
+    assert_eq!(count(&front, "<line "), 12);
+
+    // the quads of a plane share their edges, which are drawn once
+    let plane = Plane::subdivide(2, 2);
+    let vertices: Vec<_> = plane.shared_vertex_iter().collect();
+    let quads: Vec<_> = plane.indexed_polygon_iter().collect();
+    let above = Camera::new([0., -1., 4.].into(), [0., 0., 0.].into());
+    let mut out = Vec::new();
+    SvgRenderer::new(200, 200, above)
+        .cull_back_faces(true)
+        .write_indexed(&mut out, &vertices, &quads)
+        .unwrap();
+    assert_eq!(count(&String::from_utf8(out).unwrap(), "<line "), 12);
+
+    // and from below, all of them face away
+    let below = Camera::new([0., -1., -4.].into(), [0., 0., 0.].into());
+    let mut out = Vec::new();
+    SvgRenderer::new(200, 200, below)
+        .cull_back_faces(true)
+        .write_indexed(&mut out, &vertices, &quads)
+        .unwrap();
+    assert_eq!(count(&String::from_utf8(out).unwrap(), "<line "), 0);
+}
+
+#[test]
+fn svg_flat_shading() {
+    let camera = Camera::new([0., -4., 0.].into(), [0., 0., 0.].into());
+    let renderer = SvgRenderer::new(100, 100, camera)
+        .fill(Some([1., 1., 1.]))
+        .stroke(None);
+
+    let svg = render_cube(&renderer);
+    assert_eq!(count(&svg, "<polygon "), 6);
+    assert_eq!(count(&svg, "<line "), 0);
+    // the face looking straight at the camera is drawn last, fully lit
+    let last = svg.lines().rfind(|l| l.starts_with("<polygon ")).unwrap();
+    assert!(last.ends_with("fill=\"rgb(255,255,255)\"/>"));
+
+    let culled = render_cube(&renderer.clone().cull_back_faces(true));
+    assert_eq!(count(&culled, "<polygon "), 1);
+    assert_eq!(render_cube(&renderer), svg);
+}
+
+#[test]
+fn svg_near_plane() {
+    // looking out from the center, the back half of the sphere is behind the eye
+    let sphere = SphereUv::new(8, 6);
+    let vertices: Vec<_> = sphere.shared_vertex_iter().collect();
+    let polygons: Vec<_> = sphere.indexed_polygon_iter().collect();
+    let camera = Camera::new([0., 0., 0.].into(), [1., 0., 0.].into());
+    let mut out = Vec::new();
+    SvgRenderer::new(100, 100, camera)
+        .fill(Some([0.5, 0.5, 0.5]))
+        .write_indexed(&mut out, &vertices, &polygons)
+        .unwrap();
+    let svg = String::from_utf8(out).unwrap();
+    let drawn = count(&svg, "<polygon ");
+    assert!(drawn > 0 && drawn < polygons.len());
+}
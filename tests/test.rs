@@ -204,3 +204,23 @@ fn emit_lines() {
     assert_eq!(Line::new(2, 3), lines[2]);
     assert_eq!(Line::new(3, 0), lines[3]);
 }
+
+#[test]
+fn neighbors_normal_for_face() {
+    use genmesh::generators::{IndexedPolygon, SharedVertex};
+    use genmesh::Neighbors;
+
+    let plane = Plane::subdivide(2, 2);
+    let neighbors = Neighbors::new(
+        plane.shared_vertex_iter().collect::<Vec<Vertex>>(),
+        plane.indexed_polygon_iter().triangulate().collect(),
+    );
+
+    for i in 0..neighbors.polygons.len() {
+        assert_eq!(neighbors.normal_for_face(i, |v| v.pos), [0., 0., 1.].into());
+    }
+    assert_eq!(
+        neighbors.normal_for_vertex(4, |v| v.pos),
+        [0., 0., 1.].into()
+    );
+}
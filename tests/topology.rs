@@ -0,0 +1,104 @@
+use genmesh::generators::{
+    Circle, Cone, Cube, Cylinder, IcoSphere, IndexedPolygon, Plane, SphereUv, Torus,
+};
+use genmesh::{Line, TopologyReport, Triangle};
+
+#[test]
+fn topology_closed_generators() {
+    let torus = TopologyReport::new(Torus::new(1., 0.25, 12, 6).indexed_polygon_iter());
+    assert!(torus.is_closed() && torus.is_manifold());
+    assert_eq!(torus.components, vec![(0..12 * 6).collect::<Vec<_>>()]);
+    assert_eq!(torus.euler_characteristic(), 0);
+    assert_eq!(torus.genus(), Some(1));
+
+    for report in &[
+        TopologyReport::new(IcoSphere::subdivide(2).indexed_polygon_iter()),
+        TopologyReport::new(SphereUv::new(8, 6).indexed_polygon_iter()),
+    ] {
+        assert!(report.is_closed() && report.is_manifold());
+        assert!(report.degenerate_faces.is_empty());
+        assert_eq!(report.components.len(), 1);
+        assert_eq!(report.components[0].len(), report.face_count);
+        assert_eq!(report.euler_characteristic(), 2);
+        assert_eq!(report.genus(), Some(0));
+    }
+}
+
+#[test]
+fn topology_open_generators() {
+    let plane = TopologyReport::new(Plane::subdivide(3, 2).indexed_polygon_iter());
+    assert_eq!(plane.boundary_edges.len(), 10);
+    assert_eq!(plane.boundary_loops.len(), 1);
+    assert_eq!(plane.boundary_loops[0].len(), 10);
+    assert_eq!(plane.euler_characteristic(), 1);
+    assert_eq!(plane.genus(), Some(0));
+
+    // the side tube and the two caps are not connected
+    let cylinder = TopologyReport::new(Cylinder::new(8).indexed_polygon_iter());
+    assert!(cylinder.is_manifold());
+    assert_eq!(cylinder.components.len(), 3);
+    assert_eq!(
+        cylinder.components.iter().map(Vec::len).sum::<usize>(),
+        cylinder.face_count
+    );
+    assert_eq!(cylinder.boundary_loops.len(), 4);
+    assert_eq!(cylinder.genus(), Some(0));
+
+    // the faces of the cube do not share vertices
+    let cube = TopologyReport::new(Cube::new().indexed_polygon_iter());
+    let faces: Vec<Vec<usize>> = (0..6).map(|f| vec![f]).collect();
+    assert_eq!(cube.components, faces);
+    assert_eq!(cube.boundary_loops.len(), 6);
+    assert!(cube.boundary_loops.iter().all(|l| l.len() == 4));
+
+    // a fan around the center, open along its rim
+    let circle = TopologyReport::new(Circle::new(8).indexed_polygon_iter());
+    assert!(circle.is_manifold());
+    assert_eq!(circle.components, vec![(0..8).collect::<Vec<_>>()]);
+    assert_eq!(circle.boundary_loops, vec![(1..9).collect::<Vec<_>>()]);
+    assert_eq!(circle.euler_characteristic(), 1);
+    assert_eq!(circle.genus(), Some(0));
+}
+
+#[test]
+fn topology_cone() {
+    let cone = TopologyReport::new(Cone::new(8).indexed_polygon_iter());
+    // the side and the base are separate, and every side triangle has its
+    // own tip vertex, so the side is open along a zigzag through the tips
+    assert_eq!(
+        cone.components,
+        vec![(0..8).collect::<Vec<_>>(), (8..16).collect::<Vec<_>>()]
+    );
+    assert!(cone.non_manifold_edges.is_empty() && cone.degenerate_faces.is_empty());
+    assert_eq!(cone.boundary_loops.len(), 3);
+    assert_eq!(cone.boundary_loops[0].len(), 16);
+    assert_eq!(cone.boundary_loops[1], (8..16).collect::<Vec<_>>());
+    assert_eq!(cone.boundary_loops[2].len(), 8);
+    // the rim of the side is touched by the zigzag and the rim loop
+    assert_eq!(cone.non_manifold_vertices, (8..16).collect::<Vec<_>>());
+    assert_eq!(cone.genus(), None);
+}
+
+#[test]
+fn topology_defects() {
+    let triangles = [
+        // three triangles sharing the edge 0-1
+        Triangle::new(0, 1, 2),
+        Triangle::new(1, 0, 3),
+        Triangle::new(0, 1, 4),
+        // a degenerate triangle
+        Triangle::new(5, 5, 6),
+        // two triangles touching at vertex 7
+        Triangle::new(7, 8, 9),
+        Triangle::new(7, 10, 11),
+    ];
+    let report = TopologyReport::new(triangles.iter().copied());
+    assert_eq!(report.non_manifold_edges, vec![Line::new(0, 1)]);
+    assert_eq!(report.non_manifold_vertices, vec![0, 1, 7]);
+    assert_eq!(report.degenerate_faces, vec![3]);
+    assert_eq!(report.face_count, 5);
+    assert_eq!(report.vertex_count, 10);
+    assert_eq!(report.components, vec![vec![0, 1, 2], vec![4, 5]]);
+    assert!(!report.is_manifold());
+    assert_eq!(report.genus(), None);
+}
@@ -1,7 +1,11 @@
 use std::collections::HashSet;
 
 use cgmath::InnerSpace;
-use genmesh::{generators, EmitLines, Line, Lines, MapToVertices, Vertex};
+use genmesh::generators::{IndexedPolygon, SharedVertex};
+use genmesh::{
+    generators, orient_consistently, orient_outward, EmitLines, Flip, FlipWinding, Line, Lines,
+    MapToVertices, Position, Quad, Triangle, Vertex,
+};
 
 #[derive(Debug)]
 struct Edge {
@@ -153,3 +157,64 @@ fn gen_torus() {
     // convex and will fail this test.
     test_closed(generators::Torus::new(10.0, 5.0, 8, 8));
 }
+
+#[test]
+fn flip_winding_reverses() {
+    for (a, b) in generators::Cube::new().zip(generators::Cube::new().flip_winding()) {
+        assert_eq!(Quad::new(a.x, a.w, a.z, a.y), b);
+    }
+    // flipping twice restores the winding
+    test_outward(generators::IcoSphere::new().flip_winding().flip_winding());
+    test_closed(generators::SphereUv::new(4, 3).flip_winding());
+}
+
+#[test]
+fn orient_outward_repairs_spheres() {
+    let sphere = generators::IcoSphere::subdivide(1);
+    let mut vertices: Vec<Position> = sphere.shared_vertex_iter().map(|v| v.pos).collect();
+    let original: Vec<Triangle<usize>> = sphere.indexed_polygon_iter().collect();
+
+    // a second sphere off to the side, turned inside out entirely
+    let offset = vertices.len();
+    vertices.extend(
+        sphere
+            .shared_vertex_iter()
+            .map(|v| Position::from([v.pos.x + 5., v.pos.y, v.pos.z])),
+    );
+    let mut triangles: Vec<Triangle<usize>> = original.clone();
+    triangles.extend(
+        original
+            .iter()
+            .map(|t| Triangle::new(t.x + offset, t.y + offset, t.z + offset).flip()),
+    );
+
+    // scramble the first one
+    let scrambled: Vec<usize> = (0..original.len()).filter(|i| i % 3 == 1).collect();
+    for &i in &scrambled {
+        triangles[i] = triangles[i].flip();
+    }
+
+    let flipped = orient_outward(&vertices, &mut triangles, |&p| p);
+    let second: Vec<usize> = (original.len()..triangles.len()).collect();
+    assert_eq!(flipped, [&scrambled[..], &second[..]].concat());
+    assert_eq!(&triangles[..original.len()], &original[..]);
+}
+
+#[test]
+fn orient_consistently_follows_first_face() {
+    let plane = generators::Plane::subdivide(4, 4);
+    let original: Vec<Quad<usize>> = plane.indexed_polygon_iter().collect();
+    let mut quads = original.clone();
+    for q in quads.iter_mut().skip(3).step_by(2) {
+        *q = q.flip();
+    }
+    quads[0] = quads[0].flip();
+
+    let flipped = orient_consistently(&mut quads);
+    // the first quad was flipped, so everything else follows it, except
+    // for the 7 quads that were flipped already
+    assert_eq!(flipped.len(), original.len() - 1 - 7);
+    for (q, o) in quads.iter().zip(original.iter()) {
+        assert_eq!(*q, o.flip());
+    }
+}